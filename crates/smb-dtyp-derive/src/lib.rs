@@ -4,9 +4,18 @@ use proc_macro::TokenStream;
 /// Those must be installed as dependencies in the crate where this macro is used.
 ///
 /// Adds derives for `Debug`, `Default`, `Clone`, `Copy`, `PartialEq`, and `Eq`.
+///
+/// Also implements `Display`, printing the set flags as a `|`-joined list (e.g. `read|write`).
+/// Named `bool` fields are printed by name when set, and non-zero `BN` fields as `name=N`.
+/// Skipped fields and fields of any other type are not printed.
+///
+/// For reflection, generates `FIELD_NAMES`, the names of all non-skipped fields in declaration order,
+/// and `set_flags()`, returning the names of the `bool` fields that are set.
 #[proc_macro_attribute]
 pub fn mbitfield(_attr: TokenStream, input: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(input as syn::ItemStruct);
+    let display = impl_bitfield_display(&input);
+    let reflection = impl_bitfield_reflection(&input);
     quote::quote! {
         #[::modular_bitfield::bitfield]
         #[derive(::binrw::BinWrite, ::binrw::BinRead)]
@@ -14,6 +23,239 @@ pub fn mbitfield(_attr: TokenStream, input: TokenStream) -> TokenStream {
         #[bw(map = |&x| Self::into_bytes(x))]
         #[br(map = Self::from_bytes)]
         #input
+
+        #display
+
+        #reflection
     }
     .into()
 }
+
+/// Returns the named fields of a bitfield struct that have getters.
+fn bitfield_fields(input: &syn::ItemStruct) -> impl Iterator<Item = (&syn::Ident, &syn::Field)> {
+    input.fields.iter().filter_map(|field| {
+        let ident = field.ident.as_ref()?;
+        if ident.to_string().starts_with('_') || has_no_getter(&field.attrs) {
+            return None;
+        }
+        Some((ident, field))
+    })
+}
+
+fn impl_bitfield_reflection(input: &syn::ItemStruct) -> proc_macro2::TokenStream {
+    let names = bitfield_fields(input).map(|(ident, _)| ident.to_string());
+    let flags = bitfield_fields(input)
+        .filter(|(_, field)| {
+            matches!(
+                bitfield_field_kind(&field.ty),
+                Some(BitfieldFieldKind::Flag)
+            )
+        })
+        .map(|(ident, _)| {
+            let name = ident.to_string();
+            quote::quote! {
+                if self.#ident() {
+                    result.push(#name);
+                }
+            }
+        });
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    quote::quote! {
+        #[allow(dead_code)]
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// The names of all (non-skipped) fields of this bitfield, in declaration order.
+            #vis const FIELD_NAMES: &'static [&'static str] = &[#(#names),*];
+
+            /// Returns the names of the flag (`bool`) fields that are set.
+            #vis fn set_flags(&self) -> ::std::vec::Vec<&'static str> {
+                #[allow(unused_mut)]
+                let mut result = ::std::vec::Vec::new();
+                #(#flags)*
+                result
+            }
+        }
+    }
+}
+
+fn impl_bitfield_display(input: &syn::ItemStruct) -> proc_macro2::TokenStream {
+    let mut parts = Vec::new();
+    for (ident, field) in bitfield_fields(input) {
+        let name = ident.to_string();
+        match bitfield_field_kind(&field.ty) {
+            Some(BitfieldFieldKind::Flag) => parts.push(quote::quote! {
+                if self.#ident() {
+                    if !first {
+                        f.write_str("|")?;
+                    }
+                    first = false;
+                    f.write_str(#name)?;
+                }
+            }),
+            Some(BitfieldFieldKind::Bits) => parts.push(quote::quote! {
+                let value = self.#ident();
+                if value != 0 {
+                    if !first {
+                        f.write_str("|")?;
+                    }
+                    first = false;
+                    write!(f, "{}={}", #name, value)?;
+                }
+            }),
+            None => {}
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    quote::quote! {
+        impl #impl_generics ::std::fmt::Display for #name #ty_generics #where_clause {
+            #[allow(unused_mut, unused_variables, unused_assignments)]
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                let mut first = true;
+                #(#parts)*
+                Ok(())
+            }
+        }
+    }
+}
+
+enum BitfieldFieldKind {
+    /// A single-bit `bool` field.
+    Flag,
+    /// A `BN` multi-bit field.
+    Bits,
+}
+
+fn bitfield_field_kind(ty: &syn::Type) -> Option<BitfieldFieldKind> {
+    let path = match ty {
+        syn::Type::Path(path) => path,
+        // Types passed through a `macro_rules!` `ty` fragment arrive wrapped in an invisible group.
+        syn::Type::Group(group) => return bitfield_field_kind(&group.elem),
+        _ => return None,
+    };
+    let ident = path.path.get_ident()?.to_string();
+    if ident == "bool" {
+        return Some(BitfieldFieldKind::Flag);
+    }
+    match ident.strip_prefix('B') {
+        Some(bits) if bits.parse::<u8>().is_ok() => Some(BitfieldFieldKind::Bits),
+        _ => None,
+    }
+}
+
+/// Returns true if the field is marked `#[skip]` or `#[skip(getters)]`.
+fn has_no_getter(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        if !attr.path().is_ident("skip") {
+            return false;
+        }
+        match &attr.meta {
+            syn::Meta::List(list) => list.tokens.to_string().contains("getters"),
+            _ => true,
+        }
+    })
+}
+
+/// Derives `ReprEnum` and `TryFrom<Repr>` for a fieldless enum.
+///
+/// The representation type is taken from `#[brw(repr(T))]`, `#[br(repr(T))]`,
+/// `#[bw(repr(T))]` or `#[repr(T)]`, in that order.
+/// The `ReprEnum` trait and its error type are expected at `::smb_dtyp`.
+#[proc_macro_derive(ReprEnum)]
+pub fn derive_repr_enum(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    match impl_repr_enum(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn impl_repr_enum(input: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let syn::Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "ReprEnum can only be derived for enums",
+        ));
+    };
+    let repr = find_repr_type(&input.attrs)?.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.ident,
+            "ReprEnum requires a `#[brw(repr(T))]` or `#[repr(T)]` attribute",
+        )
+    })?;
+
+    let mut variants = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "ReprEnum can only be derived for fieldless enums",
+            ));
+        }
+        variants.push(&variant.ident);
+    }
+
+    let name = &input.ident;
+    let name_str = name.to_string();
+    Ok(quote::quote! {
+        impl ::smb_dtyp::ReprEnum for #name {
+            type Repr = #repr;
+
+            const VARIANTS: &'static [Self] = &[#(Self::#variants),*];
+
+            fn from_raw(value: #repr) -> ::core::option::Option<Self> {
+                #(
+                    if value == Self::#variants as #repr {
+                        return ::core::option::Option::Some(Self::#variants);
+                    }
+                )*
+                ::core::option::Option::None
+            }
+
+            fn to_raw(&self) -> #repr {
+                match self {
+                    #(
+                        Self::#variants => Self::#variants as #repr,
+                    )*
+                }
+            }
+        }
+
+        impl ::core::convert::TryFrom<#repr> for #name {
+            type Error = ::smb_dtyp::UnknownEnumValue<#repr>;
+
+            fn try_from(value: #repr) -> ::core::result::Result<Self, Self::Error> {
+                <Self as ::smb_dtyp::ReprEnum>::from_raw(value).ok_or(::smb_dtyp::UnknownEnumValue {
+                    type_name: #name_str,
+                    value,
+                })
+            }
+        }
+    })
+}
+
+/// Looks for the representation type of an enum in its attributes.
+fn find_repr_type(attrs: &[syn::Attribute]) -> syn::Result<Option<syn::Type>> {
+    for path in ["brw", "br", "bw"] {
+        for attr in attrs.iter().filter(|a| a.path().is_ident(path)) {
+            let list = attr.meta.require_list()?;
+            let mut tokens = list.tokens.clone().into_iter();
+            while let Some(token) = tokens.next() {
+                if let proc_macro2::TokenTree::Ident(ident) = &token
+                    && ident == "repr"
+                    && let Some(proc_macro2::TokenTree::Group(group)) = tokens.next()
+                {
+                    return syn::parse2(group.stream()).map(Some);
+                }
+            }
+        }
+    }
+    attrs
+        .iter()
+        .find(|a| a.path().is_ident("repr"))
+        .map(|a| a.parse_args())
+        .transpose()
+}
@@ -19,6 +19,8 @@ impl FileTime {
     const EPOCH: PrimitiveDateTime = datetime!(1601-01-01 00:00:00);
     const SCALE_VALUE_TO_NANOS: u64 = 100;
     const SCALE_VALUE_TO_SECS: u64 = 1_000_000_000 / Self::SCALE_VALUE_TO_NANOS;
+    /// Seconds between the FILETIME epoch (1601-01-01) and the Unix epoch (1970-01-01).
+    const UNIX_EPOCH_OFFSET_SECS: i64 = 11_644_473_600;
 
     /// Converts the FileTime to a PrimitiveDateTime.
     ///
@@ -46,6 +48,29 @@ impl FileTime {
         let nanos = self.value % Self::SCALE_VALUE_TO_SECS * Self::SCALE_VALUE_TO_NANOS;
         Duration::new(secs, nanos as u32)
     }
+
+    /// Creates a FileTime from seconds since the Unix epoch (January 1, 1970).
+    ///
+    /// Negative values (pre-1970 timestamps) are supported, down to the FILETIME epoch.
+    /// Returns `None` if the timestamp is not representable as a FileTime.
+    pub fn from_unix_secs(secs: i64) -> Option<Self> {
+        let secs = u64::try_from(secs.checked_add(Self::UNIX_EPOCH_OFFSET_SECS)?).ok()?;
+        let value = secs.checked_mul(Self::SCALE_VALUE_TO_SECS)?;
+        Some(Self { value })
+    }
+
+    /// Returns the number of whole seconds since the Unix epoch (January 1, 1970).
+    ///
+    /// Sub-second precision is truncated towards the FILETIME epoch,
+    /// so pre-1970 timestamps with a fractional part are rounded down.
+    pub fn to_unix_secs(&self) -> i64 {
+        (self.value / Self::SCALE_VALUE_TO_SECS) as i64 - Self::UNIX_EPOCH_OFFSET_SECS
+    }
+
+    /// Returns `None` if this is the zero ("not set") FileTime, and `Some(self)` otherwise.
+    pub fn to_option(self) -> Option<Self> {
+        if self.is_zero() { None } else { Some(self) }
+    }
 }
 
 impl Display for FileTime {
@@ -96,6 +121,24 @@ impl From<FileTime> for SystemTime {
     }
 }
 
+impl TryFrom<SystemTime> for FileTime {
+    type Error = &'static str;
+
+    fn try_from(src: SystemTime) -> Result<Self, Self::Error> {
+        let unix_offset = Duration::from_secs(Self::UNIX_EPOCH_OFFSET_SECS as u64);
+        let since_epoch = match src.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(after_unix) => after_unix + unix_offset,
+            Err(before_unix) => unix_offset
+                .checked_sub(before_unix.duration())
+                .ok_or("SystemTime is before the FILETIME epoch")?,
+        };
+        let value = since_epoch.as_nanos() / Self::SCALE_VALUE_TO_NANOS as u128;
+        Ok(Self {
+            value: u64::try_from(value).map_err(|_| "SystemTime is out of FILETIME range")?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -121,5 +164,45 @@ mod tests {
         let ft = FileTime::ZERO;
         assert!(ft.is_zero());
         assert_eq!(ft.date_time(), FileTime::EPOCH);
+        assert_eq!(ft.to_option(), None);
+        assert_eq!(
+            FileTime::from(TEST_VAL1_U64).to_option(),
+            Some(FileTime::from(TEST_VAL1_U64))
+        );
+    }
+
+    #[test]
+    pub fn test_file_time_unix_secs() {
+        assert_eq!(
+            FileTime::from_unix_secs(0),
+            Some(FileTime::from(datetime!(1970-01-01 00:00:00)))
+        );
+        assert_eq!(FileTime::from(TEST_VAL1_U64).to_unix_secs(), 1737387380);
+        let pre_unix = FileTime::from(datetime!(1969-07-20 20:17:40));
+        assert_eq!(pre_unix.to_unix_secs(), -14182940);
+        assert_eq!(FileTime::from_unix_secs(-14182940), Some(pre_unix));
+        assert_eq!(
+            FileTime::from_unix_secs(-11_644_473_600),
+            Some(FileTime::ZERO)
+        );
+        assert_eq!(FileTime::from_unix_secs(-11_644_473_601), None);
+        assert_eq!(FileTime::from_unix_secs(i64::MAX), None);
+    }
+
+    #[test]
+    pub fn test_file_time_system_time_roundtrip() {
+        let ft = FileTime::from(TEST_VAL1_U64);
+        let st: SystemTime = ft.into();
+        assert_eq!(FileTime::try_from(st), Ok(ft));
+
+        let pre_unix = FileTime::from(datetime!(1969-07-20 20:17:40.1234567));
+        let st: SystemTime = pre_unix.into();
+        assert!(st < SystemTime::UNIX_EPOCH);
+        assert_eq!(FileTime::try_from(st), Ok(pre_unix));
+
+        assert_eq!(
+            FileTime::try_from(SystemTime::from(FileTime::ZERO)),
+            Ok(FileTime::ZERO)
+        );
     }
 }
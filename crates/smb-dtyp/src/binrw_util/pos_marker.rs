@@ -3,6 +3,13 @@ use std::{fmt::Debug, io::SeekFrom, sync::OnceLock};
 
 /**
  * Source: <https://github.com/jam1garner/binrw/discussions/229>
+ *
+ * A `PosMarker` behaves differently depending on the direction:
+ * - When reading, `value` holds the resolved offset/size as parsed from the stream,
+ *   and `pos` holds the position it was read from. Use [`PosMarker::get`] to access it.
+ * - When writing, a placeholder (`T::default()`) is written and `pos` is recorded,
+ *   so the actual value can be filled in later by one of the `write_*` helpers.
+ *   In that case, `value` is not written and is left untouched.
  */
 #[derive(Default, PartialEq, Eq)]
 pub struct PosMarker<T> {
@@ -19,6 +26,26 @@ impl<T> PosMarker<T> {
         }
     }
 
+    /// Returns the value of the PosMarker.
+    ///
+    /// This is the resolved value when the PosMarker was read from a stream.
+    #[inline]
+    pub fn get(&self) -> T
+    where
+        T: Copy,
+    {
+        self.value
+    }
+
+    /// Returns true if the value of the PosMarker equals `T::default()` (zero for integers).
+    #[inline]
+    pub fn is_zero(&self) -> bool
+    where
+        T: PartialEq + Default,
+    {
+        self.value == T::default()
+    }
+
     /// Returns a [SeekFrom] that seeks relative from the position of the PosMarker.
     #[inline]
     pub fn seek_from(&self, offset: u64) -> SeekFrom {
@@ -191,6 +218,24 @@ where
         Ok(())
     }
 
+    /// Writer for the last value of a structure
+    /// * fill relative offset to offset location relative to base.
+    /// * fill the size of the whole structure, starting at base, to size location.
+    #[binrw::writer(writer, endian)]
+    pub fn write_roff_b_total<U, B>(
+        value: &U,
+        write_offset_to: &Self,
+        offset_relative_to: &PosMarker<B>,
+        write_size_to: &Self,
+    ) -> BinResult<()>
+    where
+        U: BinWrite<Args<'static> = ()>,
+    {
+        Self::write_roff_b(value, writer, endian, (write_offset_to, offset_relative_to))?;
+        let total_size = writer.stream_position()? - offset_relative_to.get_pos()?;
+        write_size_to.write_back(total_size, writer, endian)
+    }
+
     /// Writer for value
     /// * fill relative offset to offset location.
     #[binrw::writer(writer, endian)]
@@ -656,3 +701,23 @@ where
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_get_and_is_zero() {
+        let mut reader = Cursor::new([0x00, 0x00, 0x10, 0x00, 0x00, 0x00]);
+        reader.set_position(2);
+        let marker = PosMarker::<u32>::read_le(&mut reader).unwrap();
+        assert_eq!(marker.get(), 0x10);
+        assert!(!marker.is_zero());
+        assert_eq!(marker.pos.get(), Some(&2));
+
+        assert!(PosMarker::<u32>::default().is_zero());
+        assert!(PosMarker::new(0u16).is_zero());
+        assert_eq!(PosMarker::new(7u64).get(), 7);
+    }
+}
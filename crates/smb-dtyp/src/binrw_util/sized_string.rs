@@ -22,6 +22,21 @@ impl<T> BaseSizedString<T> {
     pub fn size(&self) -> u64 {
         self.data.len() as u64 * Self::CHAR_WIDTH
     }
+
+    /// Length of the string's data, in bytes, as it would be serialized.
+    ///
+    /// Same as [`size()`][Self::size], but as a `usize`.
+    pub fn byte_len(&self) -> usize {
+        self.size() as usize
+    }
+
+    /// Length of the string, in code units of `T`.
+    ///
+    /// For a [`SizedWideString`], this is the number of UTF-16 code units, which is
+    /// not the number of characters: a character outside the BMP takes two code units.
+    pub fn char_len(&self) -> usize {
+        self.data.len()
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -264,6 +279,61 @@ impl PartialEq<&str> for SizedWideString {
     }
 }
 
+impl SizedWideString {
+    /// Creates a string from raw UTF-16LE bytes.
+    ///
+    /// The bytes are not validated as UTF-16, but must be of even length.
+    pub fn from_utf16le_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        if !bytes.len().is_multiple_of(2) {
+            return Err("UTF-16LE byte length must be even");
+        }
+        Ok(bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect())
+    }
+
+    /// Checks whether the string is equal to `other`, ignoring ASCII case.
+    ///
+    /// This is useful for matching file names returned by the server against a requested name,
+    /// since SMB paths are usually case-insensitive.
+    /// Non-ASCII characters must match exactly; see [`eq_ignore_case`][Self::eq_ignore_case].
+    pub fn eq_ignore_ascii_case(&self, other: &str) -> bool {
+        let fold = |c: u16| match u8::try_from(c) {
+            Ok(b) => b.to_ascii_lowercase() as u16,
+            Err(_) => c,
+        };
+        other
+            .encode_utf16()
+            .map(fold)
+            .eq(self.data.iter().copied().map(fold))
+    }
+
+    /// Checks whether the string is equal to `other`, ignoring case for all Unicode characters.
+    ///
+    /// Characters are compared by their simple (single character) upper-case mapping,
+    /// similar to the way Windows file systems compare names. Invalid UTF-16 never matches.
+    pub fn eq_ignore_case(&self, other: &str) -> bool {
+        let fold = |c: char| {
+            let mut upper = c.to_uppercase();
+            match (upper.next(), upper.next()) {
+                (Some(u), None) => u,
+                // Multi-character mappings (e.g. 'ß' -> "SS") are not simple mappings.
+                _ => c,
+            }
+        };
+        let mut this = char::decode_utf16(self.data.iter().copied());
+        let mut other = other.chars();
+        loop {
+            match (this.next(), other.next()) {
+                (None, None) => return true,
+                (Some(Ok(a)), Some(b)) if fold(a) == fold(b) => {}
+                _ => return false,
+            }
+        }
+    }
+}
+
 impl fmt::Display for SizedWideString {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         display_utf16(&self.data, f, core::iter::once)
@@ -309,4 +379,52 @@ mod tests {
     }
     make_sized_string_tests!(test_ansi_peq, u8);
     make_sized_string_tests!(test_wide_peq, u16);
+
+    #[test]
+    fn test_wide_eq_ignore_case() {
+        use super::*;
+        let name = SizedWideString::from("hello");
+        assert!(name.eq_ignore_ascii_case("HELLO"));
+        assert!(name.eq_ignore_ascii_case("HeLLo"));
+        assert!(!name.eq_ignore_ascii_case("HELLO!"));
+        assert!(!name.eq_ignore_ascii_case("HELL"));
+
+        let name = SizedWideString::from("Ünïcode-ß.txt");
+        assert!(!name.eq_ignore_ascii_case("ÜNÏCODE-ß.TXT"));
+        assert!(name.eq_ignore_case("üNÏCODE-ß.TXT"));
+        assert!(!name.eq_ignore_case("üNÏCODE-SS.TXT"));
+        assert!(!name.eq_ignore_case("üNÏCODE-ß.TX"));
+    }
+
+    #[test]
+    fn test_wide_from_utf16le_bytes() {
+        use super::*;
+        let bytes = [0x68, 0x00, 0x69, 0x00, 0x3d, 0xd8, 0x00, 0xde];
+        let s = SizedWideString::from_utf16le_bytes(&bytes).unwrap();
+        assert_eq!(s.to_string(), "hi\u{1F600}");
+        assert_eq!(
+            SizedWideString::from(s.to_string().as_str())
+                .iter()
+                .flat_map(|c| c.to_le_bytes())
+                .collect::<Vec<_>>(),
+            bytes
+        );
+
+        assert!(SizedWideString::from_utf16le_bytes(&bytes[..3]).is_err());
+        assert!(SizedWideString::from_utf16le_bytes(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_wide_lengths() {
+        use super::*;
+        let ascii = SizedWideString::from("hello");
+        assert_eq!(ascii.char_len(), 5);
+        assert_eq!(ascii.byte_len(), 10);
+        assert_eq!(ascii.byte_len() as u64, ascii.size());
+
+        // U+1F600 is outside the BMP, and is encoded as a surrogate pair.
+        let non_bmp = SizedWideString::from("a\u{1F600}");
+        assert_eq!(non_bmp.char_len(), 3);
+        assert_eq!(non_bmp.byte_len(), 6);
+    }
 }
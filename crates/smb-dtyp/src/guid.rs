@@ -1,11 +1,17 @@
 use std::{fmt::Display, io::Cursor, str::FromStr};
 
 use binrw::prelude::*;
-use rand::{Rng, rngs::OsRng};
 
 /// Represents a standard, 16-byte GUID.
 ///
 /// Supports [`std::mem::size_of`].
+///
+/// ## Byte order
+/// - The wire layout (binrw read/write, and the `[u8; 16]` conversions) is the little-endian,
+///   Microsoft GUID layout, where the first three fields are stored in little-endian order.
+///   See [`Guid::to_bytes_le`].
+/// - The string form (`Display` and `FromStr`, e.g. `065eadf1-6daf-1543-b04f-10e69084c9ae`)
+///   follows RFC 4122, which corresponds to the big-endian byte order - [`Guid::to_bytes_be`].
 #[derive(BinRead, BinWrite, Clone, Copy, PartialEq, Eq, Default)]
 #[brw(little)]
 pub struct Guid(u32, u16, u16, [u8; 8]);
@@ -18,11 +24,28 @@ impl Guid {
     pub const ZERO: Guid = Guid(0, 0, 0, [0; 8]);
 
     /// Generates a new random GUID.
+    ///
+    /// All 128 bits are random; use [`Guid::new_v4`] for an RFC 4122 version 4 GUID.
+    #[cfg(feature = "rand")]
     pub fn generate() -> Self {
-        let mut rng = OsRng;
+        use rand::{Rng, rngs::OsRng};
         let mut bytes = [0u8; 16];
-        rng.fill(&mut bytes);
-        Self::try_from(&bytes).unwrap()
+        OsRng.fill(&mut bytes);
+        Self::from(bytes)
+    }
+
+    /// Generates a new random, RFC 4122 version 4 GUID.
+    ///
+    /// Suitable wherever a fresh unique GUID is required, such as the create GUID
+    /// of durable handle V2 requests.
+    #[cfg(feature = "rand")]
+    pub fn new_v4() -> Self {
+        let mut guid = Self::generate();
+        // Version 4 in the high nibble of Data3
+        guid.2 = (guid.2 & 0x0fff) | 0x4000;
+        // RFC 4122 variant (0b10) in the high bits of Data4[0]
+        guid.3[0] = (guid.3[0] & 0x3f) | 0x80;
+        guid
     }
 
     /// The maximum possible GUID value (all bits set to 1).
@@ -79,14 +102,63 @@ impl Guid {
         ))
     }
 
-    /// Returns the GUID as a `u128` value.
+    /// Returns the GUID as a `u128` value, interpreting the wire (little-endian) bytes as a little-endian integer.
+    ///
+    /// This is the inverse of [`Guid::from_u128`].
     pub fn as_u128(&self) -> u128 {
+        u128::from_le_bytes(self.to_bytes_le())
+    }
+
+    /// Creates a GUID from a `u128` value, as returned by [`Guid::as_u128`].
+    pub fn from_u128(value: u128) -> Self {
+        Self::from_bytes_le(value.to_le_bytes())
+    }
+
+    /// Returns the bytes of the GUID in the wire (little-endian, Microsoft) layout.
+    pub fn to_bytes_le(&self) -> [u8; 16] {
         let mut bytes = [0u8; 16];
-        {
-            let mut cursor = Cursor::new(&mut bytes[..]);
-            self.write(&mut cursor).unwrap();
-        }
-        u128::from_le_bytes(bytes)
+        bytes[..4].copy_from_slice(&self.0.to_le_bytes());
+        bytes[4..6].copy_from_slice(&self.1.to_le_bytes());
+        bytes[6..8].copy_from_slice(&self.2.to_le_bytes());
+        bytes[8..].copy_from_slice(&self.3);
+        bytes
+    }
+
+    /// Returns the bytes of the GUID in the big-endian (RFC 4122, network) order,
+    /// which is the order of the hex digits in its string form.
+    pub fn to_bytes_be(&self) -> [u8; 16] {
+        let mut bytes = [0u8; 16];
+        bytes[..4].copy_from_slice(&self.0.to_be_bytes());
+        bytes[4..6].copy_from_slice(&self.1.to_be_bytes());
+        bytes[6..8].copy_from_slice(&self.2.to_be_bytes());
+        bytes[8..].copy_from_slice(&self.3);
+        bytes
+    }
+
+    /// Creates a GUID from bytes in the wire (little-endian, Microsoft) layout.
+    pub fn from_bytes_le(bytes: [u8; 16]) -> Self {
+        Guid(
+            u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            u16::from_le_bytes([bytes[4], bytes[5]]),
+            u16::from_le_bytes([bytes[6], bytes[7]]),
+            [
+                bytes[8], bytes[9], bytes[10], bytes[11], bytes[12], bytes[13], bytes[14],
+                bytes[15],
+            ],
+        )
+    }
+
+    /// Creates a GUID from bytes in the big-endian (RFC 4122, network) order.
+    pub fn from_bytes_be(bytes: [u8; 16]) -> Self {
+        Guid(
+            u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            u16::from_be_bytes([bytes[4], bytes[5]]),
+            u16::from_be_bytes([bytes[6], bytes[7]]),
+            [
+                bytes[8], bytes[9], bytes[10], bytes[11], bytes[12], bytes[13], bytes[14],
+                bytes[15],
+            ],
+        )
     }
 }
 
@@ -115,7 +187,7 @@ pub use guid as make_guid;
 
 impl From<[u8; 16]> for Guid {
     fn from(value: [u8; 16]) -> Self {
-        Self::try_from(&value).unwrap()
+        Self::from_bytes_le(value)
     }
 }
 
@@ -130,9 +202,7 @@ impl TryFrom<&[u8; 16]> for Guid {
 
 impl From<Guid> for [u8; 16] {
     fn from(val: Guid) -> Self {
-        let mut cursor = Cursor::new(Vec::new());
-        val.write(&mut cursor).unwrap();
-        cursor.into_inner().try_into().unwrap()
+        val.to_bytes_le()
     }
 }
 
@@ -202,4 +272,43 @@ mod tests {
     test_binrw! {
         Guid: PARSED_GUID_VALUE => TEST_GUID_BYTES
     }
+
+    #[test]
+    pub fn test_guid_byte_orders() {
+        let le = PARSED_GUID_VALUE.to_bytes_le();
+        assert_eq!(le, <[u8; 16]>::from(PARSED_GUID_VALUE));
+        assert_eq!(le.as_slice(), smb_tests::hex_to_u8_array!(TEST_GUID_BYTES));
+        assert_eq!(Guid::from_bytes_le(le), PARSED_GUID_VALUE);
+
+        // Big-endian bytes are the hex digits of the string form, in order.
+        let be = PARSED_GUID_VALUE.to_bytes_be();
+        let string_hex = TEST_GUID_STR.replace('-', "");
+        assert_eq!(
+            be.as_slice(),
+            smb_tests::hex_to_u8_array!(string_hex.as_str())
+        );
+        assert_eq!(Guid::from_bytes_be(be), PARSED_GUID_VALUE);
+
+        let value = PARSED_GUID_VALUE.as_u128();
+        assert_eq!(value, u128::from_le_bytes(le));
+        assert_eq!(Guid::from_u128(value), PARSED_GUID_VALUE);
+        assert_eq!(Guid::from_u128(u128::MAX), Guid::MAX);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    pub fn test_guid_new_v4() {
+        for _ in 0..32 {
+            let guid = Guid::new_v4();
+            let s = guid.to_string();
+            assert_eq!(s.as_bytes()[14], b'4', "version nibble of {s}");
+            assert!(
+                matches!(s.as_bytes()[19], b'8' | b'9' | b'a' | b'b'),
+                "variant of {s}"
+            );
+            assert_eq!(guid.2 >> 12, 4);
+            assert_eq!(guid.3[0] >> 6, 0b10);
+        }
+        assert_ne!(Guid::new_v4(), Guid::new_v4());
+    }
 }
@@ -5,10 +5,12 @@
 
 pub mod binrw_util;
 pub mod guid;
+pub mod repr_enum;
 pub mod security;
 pub mod util;
 
 pub use guid::*;
+pub use repr_enum::*;
 pub use security::*;
 
-pub use smb_dtyp_derive::mbitfield;
+pub use smb_dtyp_derive::{ReprEnum, mbitfield};
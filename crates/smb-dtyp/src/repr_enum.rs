@@ -0,0 +1,38 @@
+//! Conversions between fieldless enums and their raw integer representation.
+
+use std::fmt::{self, Debug, Display, LowerHex};
+
+/// A fieldless enum with a fixed integer representation,
+/// such as the `#[brw(repr(u16))]` enums used in SMB messages.
+///
+/// Implement it using `#[derive(ReprEnum)]`, which also implements `TryFrom<Repr>`.
+pub trait ReprEnum: Sized + 'static {
+    /// The raw representation of the enum.
+    type Repr: Copy;
+
+    /// All the variants of the enum, in declaration order.
+    const VARIANTS: &'static [Self];
+
+    /// Converts a raw value to the matching variant, or returns `None` if there is no such variant.
+    fn from_raw(value: Self::Repr) -> Option<Self>;
+
+    /// Returns the raw value of this variant.
+    fn to_raw(&self) -> Self::Repr;
+}
+
+/// The error returned when converting a raw value that matches no variant of a [`ReprEnum`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownEnumValue<T> {
+    /// The name of the enum type.
+    pub type_name: &'static str,
+    /// The raw value that was converted.
+    pub value: T,
+}
+
+impl<T: LowerHex> Display for UnknownEnumValue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown {} value: {:#x}", self.type_name, self.value)
+    }
+}
+
+impl<T: LowerHex + Debug> std::error::Error for UnknownEnumValue<T> {}
@@ -4,6 +4,7 @@ pub mod ace;
 pub mod acl;
 pub mod security_descriptor;
 pub mod sid;
+pub mod well_known;
 pub use ace::*;
 pub use acl::*;
 pub use security_descriptor::*;
@@ -16,6 +16,10 @@ use super::SID;
 ///
 /// It's input is the name of the struct to generate, and in {}, the list of fields to add
 /// before the common fields. include support for `#[skip]` fields, without visibility (all fields are public).
+///
+/// The struct is generated using [`mbitfield`][crate::mbitfield], so it also implements [`Display`][std::fmt::Display],
+/// listing the names of the set rights (e.g. `file_read_data|delete|synchronize`).
+/// It also converts from and into the raw `u32` mask.
 #[macro_export]
 macro_rules! access_mask {
     (
@@ -27,10 +31,7 @@ macro_rules! access_mask {
         )*
     }) => {
 
-    #[::modular_bitfield::bitfield]
-    #[derive(::binrw::BinWrite, ::binrw::BinRead, Debug, Default, Clone, Copy, PartialEq, Eq)]
-    #[bw(map = |&x| Self::into_bytes(x))]
-    #[br(map = Self::from_bytes)]
+    #[$crate::mbitfield]
     $(#[$meta])*
     ///
     /// _This struct was partially generated by the [`smb_dtyp::access_mask!`][crate::access_mask] macro.
@@ -78,6 +79,18 @@ macro_rules! access_mask {
             /// (generic) Read access
             pub generic_read: bool,
         }
+
+        impl From<u32> for $name {
+            fn from(value: u32) -> Self {
+                Self::from_bytes(value.to_le_bytes())
+            }
+        }
+
+        impl From<$name> for u32 {
+            fn from(value: $name) -> Self {
+                u32::from_le_bytes(value.into_bytes())
+            }
+        }
     };
 
 }
@@ -124,6 +137,17 @@ pub enum AceValue {
         #[br(pre_assert(matches!(ace_type, AceType::$type)))]
         $type($val),
     )+
+    /// An ACE of a type that is not supported by this crate.
+    #[br(pre_assert(!matches!(ace_type, $(AceType::$type)|+)))]
+    Unknown {
+        /// The type of the ACE.
+        #[br(calc = ace_type)]
+        #[bw(ignore)]
+        ace_type: AceType,
+        /// The raw ACE data, following the ACE header.
+        #[br(parse_with = binrw::helpers::until_eof)]
+        data: Vec<u8>,
+    },
 }
 
 impl AceValue {
@@ -132,6 +156,7 @@ impl AceValue {
             $(
                 AceValue::$type(_) => AceType::$type,
             )+
+            AceValue::Unknown { ace_type, .. } => *ace_type,
         }
     }
 
@@ -182,6 +207,51 @@ make_ace_value! {
 }
 
 impl AceValue {
+    /// Returns the SID the ACE applies to, or `None` for unknown ACEs.
+    pub fn sid(&self) -> Option<&SID> {
+        match self {
+            AceValue::AccessAllowed(ace)
+            | AceValue::AccessDenied(ace)
+            | AceValue::SystemAudit(ace)
+            | AceValue::SystemScopedPolicyId(ace) => Some(&ace.sid),
+            AceValue::AccessAllowedObject(ace)
+            | AceValue::AccessDeniedObject(ace)
+            | AceValue::SystemAuditObject(ace) => Some(&ace.sid),
+            AceValue::AccessAllowedCallback(ace)
+            | AceValue::AccessDeniedCallback(ace)
+            | AceValue::SystemAuditCallback(ace) => Some(&ace.sid),
+            AceValue::AccessAllowedCallbackObject(ace)
+            | AceValue::AccessDeniedCallbackObject(ace)
+            | AceValue::SystemAuditCallbackObject(ace) => Some(&ace.sid),
+            AceValue::SystemMandatoryLabel(ace) => Some(&ace.sid),
+            AceValue::SystemResourceAttribute(ace) => Some(&ace.sid),
+            AceValue::Unknown { .. } => None,
+        }
+    }
+
+    /// Returns the raw access mask of the ACE, or `None` for unknown ACEs.
+    pub fn mask(&self) -> Option<u32> {
+        let bytes = match self {
+            AceValue::AccessAllowed(ace)
+            | AceValue::AccessDenied(ace)
+            | AceValue::SystemAudit(ace)
+            | AceValue::SystemScopedPolicyId(ace) => ace.access_mask.into_bytes(),
+            AceValue::AccessAllowedObject(ace)
+            | AceValue::AccessDeniedObject(ace)
+            | AceValue::SystemAuditObject(ace) => ace.access_mask.into_bytes(),
+            AceValue::AccessAllowedCallback(ace)
+            | AceValue::AccessDeniedCallback(ace)
+            | AceValue::SystemAuditCallback(ace) => ace.access_mask.into_bytes(),
+            AceValue::AccessAllowedCallbackObject(ace)
+            | AceValue::AccessDeniedCallbackObject(ace)
+            | AceValue::SystemAuditCallbackObject(ace) => ace.access_mask.into_bytes(),
+            AceValue::SystemMandatoryLabel(ace) => ace.mask.into_bytes(),
+            AceValue::SystemResourceAttribute(ace) => ace.mask.into_bytes(),
+            AceValue::Unknown { .. } => return None,
+        };
+        Some(u32::from_le_bytes(bytes))
+    }
+
     /// Returns true if the ACE is an "access allowed" type.
     pub fn is_access_allowed(&self) -> bool {
         matches!(
@@ -321,6 +391,18 @@ pub struct ClaimSecurityAttributeRelativeV1 {
     pub value: Vec<u8>, // TODO: Use concrete types
 }
 
+impl Default for ClaimSecurityAttributeRelativeV1 {
+    /// An empty claim, with no values.
+    fn default() -> Self {
+        Self {
+            value_type: ClaimSecurityAttributeType::None,
+            flags: FciClaimSecurityAttributes::new(),
+            value_count: 0,
+            value: vec![],
+        }
+    }
+}
+
 #[binrw::binrw]
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 #[brw(repr(u16))]
@@ -352,10 +434,52 @@ pub struct FciClaimSecurityAttributes {
     __: B6,
 }
 
+macro_rules! make_ace_type {
+    (
+        $($name:ident = $value:literal,)+
+    ) => {
+
+/// The type of an [`ACE`].
+///
+/// ACE types that are not defined here are parsed as [`AceType::Unknown`].
 #[binrw::binrw]
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-#[brw(repr(u8))]
+#[br(map = |x: u8| Self::from(x))]
+#[bw(map = |x: &Self| u8::from(*x))]
 pub enum AceType {
+    $(
+        $name,
+    )+
+    /// An ACE type that is not defined in this enum.
+    Unknown(u8),
+}
+
+impl From<u8> for AceType {
+    fn from(value: u8) -> Self {
+        match value {
+            $(
+                $value => AceType::$name,
+            )+
+            _ => AceType::Unknown(value),
+        }
+    }
+}
+
+impl From<AceType> for u8 {
+    fn from(value: AceType) -> Self {
+        match value {
+            $(
+                AceType::$name => $value,
+            )+
+            AceType::Unknown(value) => value,
+        }
+    }
+}
+
+    };
+}
+
+make_ace_type! {
     AccessAllowed = 0,
     AccessDenied = 1,
     SystemAudit = 2,
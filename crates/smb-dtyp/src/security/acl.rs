@@ -33,6 +33,11 @@ pub struct ACL {
 impl ACL {
     const HEADER_SIZE: u64 = 8;
 
+    /// Returns the ACEs in the ACL, in order.
+    pub fn aces(&self) -> &[ACE] {
+        &self.ace
+    }
+
     /// Orders the ACEs in the ACL according to the standard order.
     ///
     /// Note that since we do not have sufficient information about the inheritance,
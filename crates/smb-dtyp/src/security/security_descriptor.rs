@@ -4,7 +4,7 @@ use binrw::prelude::*;
 
 use crate::binrw_util::prelude::*;
 
-use super::{ACL, SID};
+use super::{ACE, ACL, AccessAce, AccessMask, AceFlags, AceValue, AclRevision, SID};
 
 /// Security Descriptor - [MS-DTYP 2.4.6](<https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-dtyp/7d4dac05-9cef-4563-a058-f108abecce1d>)
 #[binrw::binrw]
@@ -83,3 +83,97 @@ pub struct SecurityDescriptorControl {
     pub rm_control_valid: bool,
     pub self_relative: bool,
 }
+
+/// A builder for self-relative [`SecurityDescriptor`]s, with an owner, a group and a DACL.
+///
+/// ACEs added to the DACL are kept in the standard order (see [`ACL::order_aces`]),
+/// so access-denied ACEs always come before access-allowed ACEs, regardless of the order they were added in.
+///
+/// ```
+/// use smb_dtyp::{AccessMask, SID, SecurityDescriptorBuilder};
+/// let everyone: SID = SID::S_EVERYONE.parse().unwrap();
+/// let sd = SecurityDescriptorBuilder::new()
+///     .owner(SID::S_ADMINISTRATORS.parse().unwrap())
+///     .add_allow_ace(everyone, AccessMask::new().with_generic_read(true))
+///     .build();
+/// assert!(sd.control.dacl_present());
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct SecurityDescriptorBuilder {
+    owner_sid: Option<SID>,
+    group_sid: Option<SID>,
+    dacl: Option<ACL>,
+}
+
+impl SecurityDescriptorBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the owner SID of the security descriptor.
+    pub fn owner(mut self, sid: SID) -> Self {
+        self.owner_sid = Some(sid);
+        self
+    }
+
+    /// Sets the group SID of the security descriptor.
+    pub fn group(mut self, sid: SID) -> Self {
+        self.group_sid = Some(sid);
+        self
+    }
+
+    /// Adds an access-allowed ACE for `sid` to the DACL.
+    pub fn add_allow_ace(self, sid: SID, mask: impl Into<AccessMask>) -> Self {
+        self.add_ace(AceValue::AccessAllowed(AccessAce {
+            access_mask: mask.into(),
+            sid,
+        }))
+    }
+
+    /// Adds an access-denied ACE for `sid` to the DACL.
+    pub fn add_deny_ace(self, sid: SID, mask: impl Into<AccessMask>) -> Self {
+        self.add_ace(AceValue::AccessDenied(AccessAce {
+            access_mask: mask.into(),
+            sid,
+        }))
+    }
+
+    /// Sets an empty DACL, if no ACEs were added.
+    ///
+    /// Note that an empty DACL denies all access, while a missing DACL grants all access.
+    pub fn empty_dacl(mut self) -> Self {
+        self.dacl.get_or_insert_with(Self::new_acl);
+        self
+    }
+
+    fn add_ace(mut self, value: AceValue) -> Self {
+        self.dacl.get_or_insert_with(Self::new_acl).insert_ace(ACE {
+            ace_flags: AceFlags::new(),
+            value,
+        });
+        self
+    }
+
+    fn new_acl() -> ACL {
+        ACL {
+            acl_revision: AclRevision::Nt4,
+            ace: vec![],
+        }
+    }
+
+    /// Builds the self-relative security descriptor.
+    ///
+    /// The DACL is present only if any ACE was added, or [`empty_dacl`][Self::empty_dacl] was called.
+    pub fn build(self) -> SecurityDescriptor {
+        SecurityDescriptor {
+            sbz1: 0,
+            control: SecurityDescriptorControl::new()
+                .with_self_relative(true)
+                .with_dacl_present(self.dacl.is_some()),
+            owner_sid: self.owner_sid,
+            group_sid: self.group_sid,
+            sacl: None,
+            dacl: self.dacl,
+        }
+    }
+}
@@ -44,62 +44,80 @@ impl SID {
     /// SID common string prefix
     const PREFIX: &'static str = "S-1-";
 
+    /// Maximum number of sub-authorities in a SID.
+    pub const MAX_SUB_AUTHORITIES: usize = 15;
+
     /// Administrators group SID
     pub const S_ADMINISTRATORS: &'static str = "S-1-5-32-544";
     /// Local System user SID
     pub const S_LOCAL_SYSTEM: &'static str = "S-1-5-18";
     /// Everyone group SID
     pub const S_EVERYONE: &'static str = "S-1-1-0";
+
+    /// Creates a SID from its identifier authority and sub-authorities.
+    ///
+    /// Fails if the identifier authority does not fit in 48 bits,
+    /// or if there are more than [`SID::MAX_SUB_AUTHORITIES`] sub-authorities.
+    pub fn new(identifier_authority: u64, sub_authority: &[u32]) -> Result<Self, &'static str> {
+        if identifier_authority >> 48 != 0 {
+            return Err("Identifier authority is out of range");
+        }
+        if sub_authority.len() > Self::MAX_SUB_AUTHORITIES {
+            return Err("SID has too many sub-authorities");
+        }
+        Ok(SID {
+            identifier_authority,
+            sub_authority: sub_authority.to_vec(),
+        })
+    }
 }
 
 impl FromStr for SID {
     type Err = &'static str;
 
+    /// Parses a SID from its string form, `S-1-<authority>-<sub-authority>...`.
+    ///
+    /// The identifier authority may be either decimal, or hexadecimal with a `0x` prefix.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // 1. starts with S-1-:
-        if !s.starts_with(Self::PREFIX) {
-            return Err("SID must start with S-1-");
+        // MS-DTYP 2.4.2.1: SID String Format
+        let mut parts = s
+            .strip_prefix("S-")
+            .ok_or("SID must start with S-")?
+            .split('-');
+        // 1. revision must be 1.
+        match parts.next() {
+            Some("1") => {}
+            Some(_) => return Err("SID revision is not supported"),
+            None => return Err("SID format is incorrect - missing revision"),
         }
-        let mut s = s[Self::PREFIX.len()..].split('-');
-        // 2. authority is a number, possibly in hex.
-        let identifier_authority = match s.next() {
-            Some("0x") => {
-                // hex is only for sub-authorities > 32 bits!
-                let p = u64::from_str_radix(
-                    s.next().ok_or("Identifier authority format is incorrect")?,
-                    16,
-                )
-                .map_err(|_| "Identifier authority format is incorrect")?;
-                if p >> 32 == 0 {
-                    p
-                } else {
-                    return Err("Identifier authority format is incorrect");
-                }
-            }
-            Some(x) => x
-                .parse()
-                .map_err(|_| "Identifier authority format is incorrect")?,
+        // 2. authority is a 48-bit number, possibly in hex.
+        let identifier_authority = match parts.next() {
+            Some(x) => match x.strip_prefix("0x").or_else(|| x.strip_prefix("0X")) {
+                Some(hex) if !hex.is_empty() && hex.len() <= 12 => u64::from_str_radix(hex, 16)
+                    .map_err(|_| "Identifier authority format is incorrect")?,
+                Some(_) => return Err("Identifier authority format is incorrect"),
+                None => x
+                    .parse()
+                    .map_err(|_| "Identifier authority format is incorrect")?,
+            },
             None => return Err("SID format is incorrect - missing authority"),
         };
-        // 3. sub-authorities are numbers.
-        let sub_authority = s
+        // 3. sub-authorities are 32-bit numbers.
+        let sub_authority = parts
             .map(|x| x.parse().map_err(|_| "Sub-authority format is incorrect"))
-            .collect::<Result<_, _>>()?;
-        Ok(SID {
-            identifier_authority,
-            sub_authority,
-        })
+            .collect::<Result<Vec<u32>, _>>()?;
+        Self::new(identifier_authority, &sub_authority)
     }
 }
 
 impl std::fmt::Display for SID {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         // MS-DTYP 2.4.2.1: SID String Format
-        write!(f, "S-1-")?;
+        write!(f, "{}", Self::PREFIX)?;
         if self.identifier_authority >> 32 == 0 {
             write!(f, "{}", self.identifier_authority)?;
         } else {
-            write!(f, "0x{:x}", self.identifier_authority)?;
+            write!(f, "0x{:012X}", self.identifier_authority)?;
         }
         for sub_authority in &self.sub_authority {
             write!(f, "-{sub_authority}")?;
@@ -124,12 +142,67 @@ mod tests {
         assert_eq!(SID_STRING.parse::<SID>().unwrap(), sid_value);
         assert_eq!(sid_value.to_string(), SID_STRING);
 
-        let invalid_sids = ["", "S-1", "S-1-", "S-1-2-", "S-1-4f4"];
+        let invalid_sids = [
+            "",
+            "S-1",
+            "S-1-",
+            "S-1-2-",
+            "S-1-4f4",
+            "S-2-5-21",
+            "S--5-21",
+            "X-1-5-21",
+            "S-1-281474976710656",
+            "S-1-0x1000000000000",
+            "S-1-0x",
+            "S-1-5-4294967296",
+            "S-1-5-1-2-3-4-5-6-7-8-9-10-11-12-13-14-15-16",
+        ];
         for sid in invalid_sids {
-            assert!(sid.parse::<SID>().is_err())
+            assert!(sid.parse::<SID>().is_err(), "{sid} should be invalid")
         }
     }
 
+    #[test]
+    fn test_sid_string_edge_cases() {
+        // No sub-authorities.
+        let null_authority: SID = "S-1-0".parse().unwrap();
+        assert_eq!(null_authority.identifier_authority, 0);
+        assert!(null_authority.sub_authority.is_empty());
+        assert_eq!(null_authority.to_string(), "S-1-0");
+
+        // Large identifier authorities are printed in hex.
+        let large_authority = SID {
+            identifier_authority: 0x123456789abc,
+            sub_authority: vec![1],
+        };
+        assert_eq!(large_authority.to_string(), "S-1-0x123456789ABC-1");
+        assert_eq!(
+            "S-1-0x123456789ABC-1".parse::<SID>().unwrap(),
+            large_authority
+        );
+        assert_eq!(
+            "S-1-0x000000000005-18".parse::<SID>().unwrap().to_string(),
+            SID::S_LOCAL_SYSTEM
+        );
+        assert_eq!(
+            "S-1-20015998343868-1".parse::<SID>().unwrap(),
+            large_authority
+        );
+    }
+
+    #[test]
+    fn test_sid_new() {
+        assert_eq!(
+            SID::new(5, &[21, 782712087, 4182988437, 2163400469, 1002]).unwrap(),
+            SID_STRING.parse::<SID>().unwrap()
+        );
+        assert_eq!(SID::new(5, &[18]).unwrap().to_string(), SID::S_LOCAL_SYSTEM);
+        assert!(SID::new(0xffffffffffff, &[]).is_ok());
+        assert!(SID::new(1 << 48, &[]).is_err());
+        assert!(SID::new(5, &[0; SID::MAX_SUB_AUTHORITIES]).is_ok());
+        assert!(SID::new(5, &[0; SID::MAX_SUB_AUTHORITIES + 1]).is_err());
+    }
+
     test_binrw! {
         SID: SID_STRING.parse::<SID>().unwrap()
             => "010500000000000515000000173da72e955653f915dff280ea030000"
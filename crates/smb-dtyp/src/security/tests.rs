@@ -92,3 +92,106 @@ test_binrw! {
     00010000000000132400ff011f00010500000000000515000000173da72e95
     5653f915dff280ea030000"
 }
+
+#[test]
+fn test_security_descriptor_builder() {
+    let owner = SID::from_str("S-1-5-21-782712087-4182988437-2163400469-1001").unwrap();
+    let everyone = SID::from_str(SID::S_EVERYONE).unwrap();
+    let full_access = AccessMask::from_bytes(0x1f01ffu32.to_le_bytes());
+
+    let sd = SecurityDescriptorBuilder::new()
+        .owner(owner.clone())
+        .group(owner.clone())
+        .add_allow_ace(owner.clone(), full_access)
+        .add_deny_ace(everyone.clone(), AccessMask::new().with_delete(true))
+        .build();
+
+    assert!(sd.control.self_relative());
+    assert!(sd.control.dacl_present());
+    assert!(!sd.control.sacl_present());
+    let dacl = sd.dacl.as_ref().unwrap();
+    assert!(dacl.is_ace_sorted());
+    // Deny ACE is ordered before the allow ACE, although it was added after it.
+    assert_eq!(
+        dacl.ace[0].value,
+        AceValue::AccessDenied(AccessAce {
+            access_mask: AccessMask::new().with_delete(true),
+            sid: everyone,
+        })
+    );
+    assert_eq!(
+        dacl.ace[1].value,
+        AceValue::AccessAllowed(AccessAce {
+            access_mask: full_access,
+            sid: owner,
+        })
+    );
+
+    // Round-trip through the binary form.
+    let mut cursor = std::io::Cursor::new(Vec::new());
+    sd.write(&mut cursor).unwrap();
+    cursor.set_position(0);
+    assert_eq!(SecurityDescriptor::read(&mut cursor).unwrap(), sd);
+
+    // No ACEs - no DACL, unless explicitly requested.
+    let sd = SecurityDescriptorBuilder::new().build();
+    assert!(!sd.control.dacl_present());
+    assert!(sd.dacl.is_none());
+    let sd = SecurityDescriptorBuilder::new().empty_dacl().build();
+    assert!(sd.control.dacl_present());
+    assert!(sd.dacl.unwrap().ace.is_empty());
+}
+
+test_binrw! {
+    ACL => unknown_ace: ACL {
+        acl_revision: AclRevision::Nt4,
+        ace: vec![
+            ACE {
+                ace_flags: AceFlags::new(),
+                value: AceValue::AccessAllowed(AccessAce {
+                    access_mask: AccessMask::from_bytes(0x1f01ffu32.to_le_bytes()),
+                    sid: SID::from_str(SID::S_EVERYONE).unwrap(),
+                }),
+            },
+            ACE {
+                ace_flags: AceFlags::new(),
+                value: AceValue::AccessDenied(AccessAce {
+                    access_mask: AccessMask::new().with_delete(true),
+                    sid: SID::from_str(SID::S_EVERYONE).unwrap(),
+                }),
+            },
+            ACE {
+                ace_flags: AceFlags::new(),
+                value: AceValue::Unknown {
+                    ace_type: AceType::Unknown(0x42),
+                    data: vec![0xde, 0xad, 0xbe, 0xef],
+                },
+            },
+        ],
+    } => "020038000300000000001400ff011f000101000000000001000000000100140000000100010100000000000100000000420008
+    00deadbeef"
+}
+
+#[test]
+fn test_acl_aces() {
+    let mut cursor = std::io::Cursor::new(hex_to_u8_array!(
+        "020038000300000000001400ff011f000101000000000001000000000100140000000100010100000000000100000000420008
+    00deadbeef"
+    ));
+    let acl = ACL::read_le(&mut cursor).unwrap();
+    let everyone = SID::from_str(SID::S_EVERYONE).unwrap();
+
+    let aces = acl.aces();
+    assert_eq!(aces.len(), 3);
+    assert!(aces[0].value.is_access_allowed());
+    assert_eq!(aces[0].value.sid(), Some(&everyone));
+    assert_eq!(aces[0].value.mask(), Some(0x1f01ff));
+    assert!(aces[1].value.is_access_denied());
+    assert_eq!(aces[1].value.mask(), Some(0x10000));
+    assert_eq!(aces[2].ace_type(), AceType::Unknown(0x42));
+    assert_eq!(aces[2].value.sid(), None);
+    assert_eq!(aces[2].value.mask(), None);
+
+    assert_eq!(AceType::from(3), AceType::SystemAlarm);
+    assert_eq!(u8::from(AceType::SystemScopedPolicyId), 19);
+}
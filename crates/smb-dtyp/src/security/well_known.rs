@@ -0,0 +1,65 @@
+//! Well-known SIDs
+//!
+//! [MS-DTYP 2.4.2.4](<https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-dtyp/81d92bba-d22b-4a8c-908a-554ab29148ab>)
+//!
+//! ```
+//! use smb_dtyp::security::well_known;
+//! assert_eq!(well_known::everyone().to_string(), "S-1-1-0");
+//! ```
+
+use super::SID;
+
+/// `SECURITY_WORLD_SID_AUTHORITY`
+const WORLD_AUTHORITY: u64 = 1;
+/// `SECURITY_NT_AUTHORITY`
+const NT_AUTHORITY: u64 = 5;
+
+/// Everyone group SID (`S-1-1-0`)
+pub fn everyone() -> SID {
+    SID {
+        identifier_authority: WORLD_AUTHORITY,
+        sub_authority: vec![0],
+    }
+}
+
+/// Authenticated Users group SID (`S-1-5-11`)
+pub fn authenticated_users() -> SID {
+    SID {
+        identifier_authority: NT_AUTHORITY,
+        sub_authority: vec![11],
+    }
+}
+
+/// Local System user SID (`S-1-5-18`)
+pub fn local_system() -> SID {
+    SID {
+        identifier_authority: NT_AUTHORITY,
+        sub_authority: vec![18],
+    }
+}
+
+/// Builtin Administrators group SID (`S-1-5-32-544`)
+pub fn administrators() -> SID {
+    SID {
+        identifier_authority: NT_AUTHORITY,
+        sub_authority: vec![32, 544],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_well_known_sids() {
+        assert_eq!(everyone(), SID::from_str(SID::S_EVERYONE).unwrap());
+        assert_eq!(local_system(), SID::from_str(SID::S_LOCAL_SYSTEM).unwrap());
+        assert_eq!(
+            administrators(),
+            SID::from_str(SID::S_ADMINISTRATORS).unwrap()
+        );
+        assert_eq!(authenticated_users().to_string(), "S-1-5-11");
+    }
+}
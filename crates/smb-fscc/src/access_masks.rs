@@ -1,7 +1,7 @@
 //! Access masks definitions.
 
 use modular_bitfield::prelude::*;
-use smb_dtyp::access_mask;
+use smb_dtyp::{AccessMask, access_mask};
 
 access_mask! {
     /// File Access Mask
@@ -36,6 +36,39 @@ pub struct FileAccessMask {
     __: B7,
 }}
 
+impl FileAccessMask {
+    /// The specific rights that `GENERIC_READ` maps to for files.
+    pub const FILE_GENERIC_READ: u32 = 0x00120089;
+    /// The specific rights that `GENERIC_WRITE` maps to for files.
+    pub const FILE_GENERIC_WRITE: u32 = 0x00120116;
+    /// The specific rights that `GENERIC_EXECUTE` maps to for files.
+    pub const FILE_GENERIC_EXECUTE: u32 = 0x001200a0;
+    /// The specific rights that `GENERIC_ALL` maps to for files.
+    pub const FILE_ALL_ACCESS: u32 = 0x001f01ff;
+
+    /// Returns the mask with the generic rights replaced by the specific and standard rights they map to for files.
+    ///
+    /// See [File Security and Access Rights - MSDN](<https://learn.microsoft.com/en-us/windows/win32/fileio/file-security-and-access-rights>)
+    pub fn map_generic(self) -> Self {
+        let mut value = u32::from_le_bytes(self.into_bytes());
+        for (generic, specific) in [
+            (self.generic_read(), Self::FILE_GENERIC_READ),
+            (self.generic_write(), Self::FILE_GENERIC_WRITE),
+            (self.generic_execute(), Self::FILE_GENERIC_EXECUTE),
+            (self.generic_all(), Self::FILE_ALL_ACCESS),
+        ] {
+            if generic {
+                value |= specific;
+            }
+        }
+        Self::from_bytes(value.to_le_bytes())
+            .with_generic_read(false)
+            .with_generic_write(false)
+            .with_generic_execute(false)
+            .with_generic_all(false)
+    }
+}
+
 access_mask! {
     /// Directory Access Mask
     ///
@@ -82,3 +115,52 @@ impl From<DirAccessMask> for FileAccessMask {
         FileAccessMask::from_bytes(val.into_bytes())
     }
 }
+
+impl From<FileAccessMask> for AccessMask {
+    fn from(mask: FileAccessMask) -> Self {
+        // The file-specific rights are in the lower word, which is opaque to the generic mask.
+        Self::from_bytes(mask.into_bytes())
+    }
+}
+
+impl From<DirAccessMask> for AccessMask {
+    fn from(mask: DirAccessMask) -> Self {
+        Self::from_bytes(mask.into_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_access_mask_display() {
+        let all = FileAccessMask::from_bytes(FileAccessMask::FILE_ALL_ACCESS.to_le_bytes());
+        assert_eq!(
+            all.to_string(),
+            "file_read_data|file_write_data|file_append_data|file_read_ea|file_write_ea|file_execute|file_delete_child|file_read_attributes|file_write_attributes|delete|read_control|write_dacl|write_owner|synchronize"
+        );
+        assert_eq!(
+            FileAccessMask::new()
+                .with_generic_read(true)
+                .with_generic_all(true)
+                .to_string(),
+            "generic_all|generic_read"
+        );
+        assert_eq!(FileAccessMask::new().to_string(), "");
+        assert_eq!(FileAccessMask::FIELD_NAMES.len(), 9 + 11);
+    }
+
+    #[test]
+    fn test_access_mask_u32_conversion() {
+        let mask = FileAccessMask::from(FileAccessMask::FILE_ALL_ACCESS);
+        assert!(mask.file_read_data());
+        assert!(mask.synchronize());
+        assert!(!mask.generic_all());
+        assert_eq!(u32::from(mask), FileAccessMask::FILE_ALL_ACCESS);
+        assert_eq!(
+            DirAccessMask::from(0x80000000).into_bytes(),
+            DirAccessMask::new().with_generic_read(true).into_bytes()
+        );
+    }
+}
@@ -54,6 +54,44 @@ impl<T, const OFFSET_PAD: u32> ChainedItemList<T, OFFSET_PAD> {
     pub fn len(&self) -> usize {
         self.values.len()
     }
+
+    /// Returns a reference to the item at the given index, or `None` if out of bounds.
+    #[inline]
+    pub fn get(&self, idx: usize) -> Option<&T> {
+        self.values.get(idx)
+    }
+
+    /// Returns an iterator that allows modifying each value in the chained item list.
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.values.iter_mut()
+    }
+
+    /// Appends an item to the end of the chained item list.
+    #[inline]
+    pub fn push(&mut self, value: T) {
+        self.values.push(value);
+    }
+
+    /// Retains only the items for which `f` returns true.
+    ///
+    /// Next entry offsets are computed on write, so they remain correct after removing items.
+    #[inline]
+    pub fn retain(&mut self, f: impl FnMut(&T) -> bool) {
+        self.values.retain(f);
+    }
+
+    /// Returns the first item for which `pred` returns true, if any.
+    #[inline]
+    pub fn find(&self, pred: impl Fn(&T) -> bool) -> Option<&T> {
+        self.values.iter().find(|v| pred(v))
+    }
+
+    /// Removes all items from the chained item list.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
 }
 
 impl<T, const OFFSET_PAD: u32> BinWrite for ChainedItemList<T, OFFSET_PAD>
@@ -205,3 +243,52 @@ impl<T, const OFFSET_PAD: u32> Default for ChainedItemList<T, OFFSET_PAD> {
         Self { values: Vec::new() }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_push_get_iter_mut_clear() {
+        let mut list = ChainedItemList::<u32>::default();
+        list.push(1);
+        list.push(2);
+        list.push(3);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.get(1), Some(&2));
+        assert_eq!(list.get(3), None);
+
+        list.iter_mut().for_each(|v| *v *= 10);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![10, 20, 30]);
+
+        let mut cursor = Cursor::new(Vec::new());
+        list.write_le(&mut cursor).unwrap();
+        cursor.set_position(0);
+        assert_eq!(ChainedItemList::<u32>::read_le(&mut cursor).unwrap(), list);
+
+        list.clear();
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_retain_recomputes_offsets() {
+        let mut list: ChainedItemList<u16> = vec![1, 2, 3, 4].into();
+        list.retain(|v| v % 2 == 1);
+        assert_eq!(list.find(|v| *v > 1), Some(&3));
+        assert_eq!(list.find(|v| *v > 3), None);
+
+        let mut cursor = Cursor::new(Vec::new());
+        list.write_le(&mut cursor).unwrap();
+        assert_eq!(
+            cursor.get_ref().as_slice(),
+            &[
+                0x08, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, // first, padded to 8
+                0x00, 0x00, 0x00, 0x00, 0x03, 0x00, // last, next offset is 0
+            ]
+        );
+
+        cursor.set_position(0);
+        assert_eq!(ChainedItemList::<u16>::read_le(&mut cursor).unwrap(), list);
+    }
+}
@@ -8,7 +8,10 @@
 use binrw::prelude::*;
 
 use crate::info_classes::file_info_classes;
-use smb_dtyp::binrw_util::{fixed_string::FixedWideString, prelude::*};
+use smb_dtyp::{
+    Guid,
+    binrw_util::{fixed_string::FixedWideString, prelude::*},
+};
 
 use super::{FileAttributes, ReparseTag};
 
@@ -308,13 +311,115 @@ query_dir_type! {
     }; short_name_length short_name
 }
 
+/// Query the object ID information of a file, as listed in the `$Extend\$ObjId:$O:$INDEX_ALLOCATION` index,
+/// which is used by distributed link tracking.
+///
+/// Unlike the other directory information classes, entries of this class are returned back to back,
+/// without a `NextEntryOffset` field. Therefore, it is not part of [`QueryDirectoryInfo`],
+/// and should not be wrapped in [`ChainedItemList<T>`][crate::ChainedItemList];
+/// instead, read the entries one after the other until the end of the output buffer:
+/// ```
+/// use binrw::{helpers::until_eof, io::Cursor, prelude::*};
+/// use smb_dtyp::Guid;
+/// use smb_fscc::FileObjectIdInformation;
+///
+/// let entry = FileObjectIdInformation::new(0x23, Guid::ZERO, Guid::ZERO, Guid::ZERO, Guid::ZERO);
+/// let mut buffer = Cursor::new(Vec::new());
+/// entry.write_le(&mut buffer).unwrap();
+/// entry.write_le(&mut buffer).unwrap();
+///
+/// buffer.set_position(0);
+/// let entries: Vec<FileObjectIdInformation> = until_eof(&mut buffer, binrw::Endian::Little, ()).unwrap();
+/// assert_eq!(entries.len(), 2);
+/// assert_eq!(entries[1], entry);
+/// ```
+///
+/// [MS-FSCC 2.4](<https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-fscc/4718fc40-e539-4014-8e33-b675af74e3e1>) - FileObjectIdInformation
+#[binrw::binrw]
+#[derive(Debug, PartialEq, Eq)]
+pub struct FileObjectIdInformation {
+    /// The 64-bit file reference number of the file.
+    pub file_reference: u64,
+    /// The 16-byte object ID of the file.
+    pub object_id: Guid,
+    /// The birth volume ID, birth object ID and domain ID of the file.
+    ///
+    /// Use [`birth_volume_id()`][Self::birth_volume_id], [`birth_object_id()`][Self::birth_object_id]
+    /// and [`domain_id()`][Self::domain_id] to access the individual IDs.
+    pub extended_info: [u8; 48],
+}
+
+impl FileObjectIdInformation {
+    /// Creates a new object ID entry, packing the birth volume ID, birth object ID and domain ID
+    /// into the extended information.
+    pub fn new(
+        file_reference: u64,
+        object_id: Guid,
+        birth_volume_id: Guid,
+        birth_object_id: Guid,
+        domain_id: Guid,
+    ) -> Self {
+        let mut extended_info = [0; 48];
+        extended_info[..16].copy_from_slice(&<[u8; 16]>::from(birth_volume_id));
+        extended_info[16..32].copy_from_slice(&<[u8; 16]>::from(birth_object_id));
+        extended_info[32..].copy_from_slice(&<[u8; 16]>::from(domain_id));
+        Self {
+            file_reference,
+            object_id,
+            extended_info,
+        }
+    }
+
+    /// The object ID of the volume on which the object resided when the object ID was created.
+    pub fn birth_volume_id(&self) -> Guid {
+        self.guid_at(0)
+    }
+
+    /// The object ID of the file at the time it was created.
+    pub fn birth_object_id(&self) -> Guid {
+        self.guid_at(16)
+    }
+
+    /// The domain ID. Currently unused, and should be all zeros.
+    pub fn domain_id(&self) -> Guid {
+        self.guid_at(32)
+    }
+
+    fn guid_at(&self, offset: usize) -> Guid {
+        let bytes: [u8; 16] = self.extended_info[offset..offset + 16].try_into().unwrap();
+        Guid::from(bytes)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::ChainedItemList;
+    use smb_dtyp::make_guid;
     use smb_tests::*;
     use time::macros::datetime;
 
+    test_binrw! {
+        struct FileObjectIdInformation {
+            file_reference: 0x0001000000000023,
+            object_id: make_guid!("ed3e2170-2733-48b3-e5c0-bd5334f85a37"),
+            extended_info: hex_to_u8_array!("e0f1a5d5b6a8a14c9c2b2b4c3a7e1f0070213eed3327b348e5c0bd5334f85a3700000000000000000000000000000000").try_into().unwrap(),
+        } => "230000000000010070213eed3327b348e5c0bd5334f85a37e0f1a5d5b6a8a14c9c2b2b4c3a7e1f0070213eed3327b348e5c0bd5334f85a3700000000000000000000000000000000"
+    }
+
+    #[test]
+    fn test_object_id_accessors() {
+        let object_id = make_guid!("ed3e2170-2733-48b3-e5c0-bd5334f85a37");
+        let birth_volume_id = make_guid!("d5a5f1e0-a8b6-4ca1-9c2b-2b4c3a7e1f00");
+        let info =
+            FileObjectIdInformation::new(0x23, object_id, birth_volume_id, object_id, Guid::ZERO);
+        assert_eq!(info.file_reference, 0x23);
+        assert_eq!(info.object_id, object_id);
+        assert_eq!(info.birth_volume_id(), birth_volume_id);
+        assert_eq!(info.birth_object_id(), object_id);
+        assert_eq!(info.domain_id(), Guid::ZERO);
+    }
+
     macro_rules! make_id_all_extd_both_directory {
         ($file_index:expr, $created:expr, $access_write_time:expr, $change_time:expr, $file_name:expr, $file_id:literal) => {{
             let mut result = FileIdAllExtdBothDirectoryInformation::make_common_test_dir(
@@ -360,6 +360,26 @@ pub struct FileFsVolumeInformation {
     pub volume_label: SizedWideString,
 }
 
+impl FileFsVolumeInformation {
+    /// Returns the volume label, without any trailing null terminator or space padding.
+    pub fn volume_label_string(&self) -> String {
+        self.volume_label
+            .to_string()
+            .trim_end_matches(['\0', ' '])
+            .to_string()
+    }
+
+    /// Returns the serial number of the volume.
+    pub fn serial_number(&self) -> u32 {
+        self.volume_serial_number
+    }
+
+    /// Returns the time when the volume was created.
+    pub fn creation_time(&self) -> FileTime {
+        self.volume_creation_time
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -376,6 +396,28 @@ mod tests {
         } => "525119cd3d3cdc01f42c9d520e00000000004d00790053006800610072006500"
     }
 
+    #[test]
+    fn test_fs_volume_information_accessors() {
+        let info = FileFsVolumeInformation {
+            volume_creation_time: datetime!(2025-10-13 12:35:04.593237).into(),
+            volume_serial_number: 0x529d2cf4,
+            volume_label: "MyShare\0".into(),
+            supports_objects: false.into(),
+        };
+        assert_eq!(info.volume_label_string(), "MyShare");
+        assert_eq!(info.serial_number(), 0x529d2cf4);
+        assert_eq!(
+            info.creation_time(),
+            datetime!(2025-10-13 12:35:04.593237).into()
+        );
+
+        let padded = FileFsVolumeInformation {
+            volume_label: "Data    ".into(),
+            ..info
+        };
+        assert_eq!(padded.volume_label_string(), "Data");
+    }
+
     test_binrw! {
         struct FileFsSizeInformation {
             total_allocation_units: 61202244,
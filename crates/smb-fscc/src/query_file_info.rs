@@ -191,6 +191,13 @@ pub struct FileEaInformation {
     pub ea_size: u32,
 }
 
+impl FileEaInformation {
+    /// Returns the size in bytes of the extended attributes for the file.
+    pub fn ea_size(&self) -> u32 {
+        self.ea_size
+    }
+}
+
 /// Query the file system's 8-byte file reference number for a file.
 ///
 /// [MS-FSCC 2.4.26](<https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-fscc/e4185a8a-ed8d-4f98-ab55-ca34dc8916e6>)
@@ -419,6 +426,7 @@ impl FileGetEaInformation {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::FileInfoType;
     use smb_tests::*;
     use time::macros::datetime;
 
@@ -575,6 +583,14 @@ mod tests {
         FILE_EA_INFORMATION_FOR_TEST_STRING
     );
 
+    #[test]
+    fn test_file_ea_information_dispatch() {
+        let info: QueryFileInfo = get_file_ea_information_for_test().into();
+        assert_eq!(info.class(), QueryFileInfoClass::EaInformation);
+        let ea: FileEaInformation = info.try_into().unwrap();
+        assert_eq!(ea.ea_size(), 208);
+    }
+
     const FILE_ALL_INFORMATION_FOR_TEST_STRING: &str = const_format::concatcp!(
         FILE_BASIC_INFORMATION_FOR_TEST_STRING,
         FILE_STANDARD_INFORMATION_FOR_TEST_STRING,
@@ -2,6 +2,8 @@ use binrw::{io::TakeSeekExt, prelude::*};
 use smb_dtyp::SID;
 use smb_dtyp::binrw_util::prelude::*;
 
+use crate::ChainedItemList;
+
 /// Query or to set file quota information for a volume.
 ///
 /// For queries, an optional buffer of FILE_GET_QUOTA_INFORMATION (section 2.4.41.1) data elements is provided by the client to specify the SIDs for which quota information is requested.
@@ -39,6 +41,13 @@ impl FileQuotaInformation {
         + SID::MIN_SIZE;
 }
 
+impl<const OFFSET_PAD: u32> ChainedItemList<FileQuotaInformation, OFFSET_PAD> {
+    /// Returns the quota entry of the specified SID, if present in the list.
+    pub fn find_by_sid(&self, sid: &SID) -> Option<&FileQuotaInformation> {
+        self.iter().find(|entry| &entry.sid == sid)
+    }
+}
+
 /// This structure is used to provide the list of SIDs for which quota query information is requested.
 ///
 /// [MS-FSCC 2.4.41.1](<https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-fscc/56adae21-add4-4434-97ec-e40e87739d52>)
@@ -55,3 +64,32 @@ pub struct FileGetQuotaInformation {
     #[bw(write_with = PosMarker::write_size, args(&sid_length))]
     pub sid: SID,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_quota_find_by_sid() {
+        let quota_entry = |sid: &str, quota_used: u64| FileQuotaInformation {
+            change_time: FileTime::ZERO,
+            quota_used,
+            quota_threshold: u64::MAX,
+            quota_limit: u64::MAX,
+            sid: SID::from_str(sid).unwrap(),
+        };
+        let quotas: ChainedItemList<FileQuotaInformation> = vec![
+            quota_entry("S-1-5-32-544", 0x1000),
+            quota_entry("S-1-5-21-782712087-4182988437-2163400469-1002", 0x2000),
+        ]
+        .into();
+
+        let sid = SID::from_str("S-1-5-21-782712087-4182988437-2163400469-1002").unwrap();
+        assert_eq!(quotas.find_by_sid(&sid).unwrap().quota_used, 0x2000);
+
+        let missing = SID::from_str("S-1-5-18").unwrap();
+        assert!(quotas.find_by_sid(&missing).is_none());
+    }
+}
@@ -8,6 +8,7 @@ use std::ops::Deref;
 
 use crate::file_info_classes;
 
+use modular_bitfield::prelude::*;
 use smb_dtyp::binrw_util::prelude::*;
 
 use super::{
@@ -28,6 +29,7 @@ file_info_classes! {
         pub Pipe = 23,
         pub Position = 14,
         pub Rename = 10,
+        pub RenameEx = 65,
         pub ShortName = 40,
         pub ValidDataLength = 39,
     }
@@ -91,6 +93,54 @@ pub struct FileRenameInformation {
     pub file_name: SizedWideString,
 }
 
+/// Rename a file, with extended rename flags (`FileRenameInformationEx`).
+///
+/// This has the same layout as [`FileRenameInformation`], except that the
+/// `ReplaceIfExists` boolean is replaced by a 32-bit [`RenameExFlags`] field.
+///
+/// [MS-FSCC 2.4.42.2](<https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-fscc/52aa0b70-8094-4971-862d-79793f41e6a8>) - FileRenameInformation for SMB2 protocol
+#[binrw::binrw]
+#[derive(Debug, PartialEq, Eq)]
+pub struct FileRenameExInformation {
+    /// Flags controlling the rename operation.
+    pub flags: RenameExFlags,
+    #[bw(calc = 0)]
+    #[br(temp)]
+    _reserved: u32,
+    /// A file handle for the root directory. For network operations, this value must be zero.
+    pub root_directory: u64,
+    #[bw(try_calc = file_name.size().try_into())]
+    _file_name_length: u32,
+    /// The new name for the file, including the full path.
+    #[br(args { size: SizedStringSize::bytes(_file_name_length) })]
+    pub file_name: SizedWideString,
+}
+
+/// Flags for [`FileRenameExInformation`].
+#[smb_dtyp::mbitfield]
+pub struct RenameExFlags {
+    /// If a file with the given name already exists, it should be replaced with the given file.
+    pub replace_if_exists: bool,
+    /// Use POSIX semantics: an existing target that is still open is unlinked, rather than failing the rename.
+    pub posix_semantics: bool,
+    /// Do not inherit the pin state of the target directory.
+    pub suppress_pin_state_inheritance: bool,
+    /// Do not inherit the storage reserve ID of the target directory.
+    pub suppress_storage_reserve_inheritance: bool,
+    /// When moving across storage reserve areas, do not decrease available space.
+    pub no_decrease_available_space: bool,
+    /// When moving across storage reserve areas, do not increase available space.
+    pub no_increase_available_space: bool,
+    /// Allow replacing a target file that has the read-only attribute set.
+    pub ignore_readonly_attribute: bool,
+    /// Resize the target storage reserve area if needed.
+    pub force_resize_target_sr: bool,
+    /// Resize the source storage reserve area if needed.
+    pub force_resize_source_sr: bool,
+    #[skip]
+    __: B23,
+}
+
 /// Set the allocation size for a file.
 ///
 /// The file system is passed a 64-bit signed integer containing the file allocation size, in bytes.
@@ -216,6 +266,16 @@ mod tests {
         } => "000000000000000000000000000000000a00000062002e00740078007400"
     }
 
+    test_binrw! {
+        struct FileRenameExInformation {
+            flags: RenameExFlags::new()
+                .with_replace_if_exists(true)
+                .with_posix_semantics(true),
+            root_directory: 0,
+            file_name: SizedWideString::from("b.txt"),
+        } => "030000000000000000000000000000000a00000062002e00740078007400"
+    }
+
     test_binrw! {
         struct FileBasicInformation {
             creation_time: FileTime::ZERO,
@@ -69,11 +69,15 @@ impl Parse for SmbReqResAttr {
     }
 }
 
-fn make_size_field(size: u16) -> syn::Field {
+fn make_size_field(size: u16, name: &syn::Ident) -> syn::Field {
     // #[bw(calc = #size)]
     // #[br(temp)]
-    // #[br(assert(_structure_size == #size))]
+    // #[br(assert(_structure_size == #size, "unexpected structure size for #name: {}", _structure_size))]
     // _structure_size: u16,
+    let message = syn::LitStr::new(
+        &format!("unexpected structure size for {name}: {{}}"),
+        proc_macro2::Span::call_site(),
+    );
     syn::Field {
         attrs: vec![
             syn::parse_quote! {
@@ -83,7 +87,7 @@ fn make_size_field(size: u16) -> syn::Field {
                 #[br(temp)]
             },
             syn::parse_quote! {
-                #[br(assert(_structure_size == #size))]
+                #[br(assert(_structure_size == #size, #message, _structure_size))]
             },
         ],
         vis: syn::Visibility::Inherited,
@@ -110,19 +114,26 @@ fn modify_smb_msg(msg_type: SmbMsgType, item: TokenStream, attr: TokenStream) ->
     let mut item = parse_macro_input!(item as ItemStruct);
     let attr = parse_macro_input!(attr as SmbReqResAttr);
 
-    let size_field = make_size_field(attr.value);
-    match item.fields {
+    let size_field = make_size_field(attr.value, &item.ident);
+    let name = &item.ident;
+    let message = match item.fields {
         Fields::Named(ref mut fields) => {
             fields.named.insert(0, size_field);
+            None
         }
-        _ => {
-            return syn::Error::new_spanned(
-                &item.fields,
-                "Expected named fields for smb request/response",
-            )
+        Fields::Unnamed(_) => Some(format!(
+            "smb request/response must have named fields, since a `_structure_size` field is added to it; \
+            use `struct {name} {{ .. }}` instead of a tuple struct"
+        )),
+        Fields::Unit => Some(format!(
+            "smb request/response must have named fields, since a `_structure_size` field is added to it; \
+            use `struct {name} {{}}` for a message with no fields"
+        )),
+    };
+    if let Some(message) = message {
+        return syn::Error::new_spanned(&item.ident, message)
             .to_compile_error()
             .into();
-        }
     }
 
     TokenStream::from(quote! {
@@ -202,6 +213,7 @@ fn common_struct_changes(msg_type: SmbMsgType, item: TokenStream) -> TokenStream
 /// Proc-macro for constructing SMB request messages.
 ///
 /// Valid usage is `#[smb_request(size = <u16>)]` before a struct definition.
+/// The struct must have named fields; use `struct Name {}` for a message with no fields.
 #[proc_macro_attribute]
 pub fn smb_request(attr: TokenStream, input: TokenStream) -> TokenStream {
     modify_smb_msg(SmbMsgType::Request, input, attr)
@@ -210,6 +222,7 @@ pub fn smb_request(attr: TokenStream, input: TokenStream) -> TokenStream {
 /// Proc-macro for constructing SMB response messages.
 ///
 /// Valid usage is `#[smb_response(size = <u16>)]` before a struct definition.
+/// The struct must have named fields; use `struct Name {}` for a message with no fields.
 #[proc_macro_attribute]
 pub fn smb_response(attr: TokenStream, input: TokenStream) -> TokenStream {
     modify_smb_msg(SmbMsgType::Response, input, attr)
@@ -218,6 +231,7 @@ pub fn smb_response(attr: TokenStream, input: TokenStream) -> TokenStream {
 /// Proc-macro for constructing SMB request and response messages.
 ///
 /// Valid usage is `#[smb_request_response(size = <u16>)]` before a struct definition.
+/// The struct must have named fields; use `struct Name {}` for a message with no fields.
 #[proc_macro_attribute]
 pub fn smb_request_response(attr: TokenStream, input: TokenStream) -> TokenStream {
     modify_smb_msg(SmbMsgType::Both, input, attr)
@@ -36,6 +36,16 @@ impl CompressedMessage {
             }
         }
     }
+
+    /// Returns the size of the uncompressed message, as declared in the header.
+    ///
+    /// This value is sent by the peer, and should be validated before being used for allocations.
+    pub fn original_size(&self) -> u32 {
+        match self {
+            CompressedMessage::Unchained(m) => m.original_size,
+            CompressedMessage::Chained(m) => m.original_size,
+        }
+    }
 }
 
 /// SMB2 compression transform header for unchained compressed messages.
@@ -1,10 +1,8 @@
 //! Create & Close (files) requests and responses.
 
 use std::fmt::{Debug, Display};
-use std::io::Cursor;
-
 #[cfg(feature = "client")]
-use std::io::SeekFrom;
+use std::io::{Cursor, SeekFrom};
 
 use super::header::Status;
 use super::*;
@@ -37,18 +35,42 @@ impl FileId {
     };
 }
 
+/// Converts a `u128` to a [`FileId`], where the low 64 bits are the persistent part,
+/// and the high 64 bits are the volatile part.
+///
+/// This matches the wire layout: the little-endian bytes of the `u128`
+/// are the same as the 16-byte [`FileId`] conversion.
+impl From<u128> for FileId {
+    fn from(value: u128) -> Self {
+        Self {
+            persistent: value as u64,
+            volatile: (value >> 64) as u64,
+        }
+    }
+}
+
+/// The inverse of `From<u128> for FileId`: the persistent part is stored in the low 64 bits.
+impl From<FileId> for u128 {
+    fn from(file_id: FileId) -> Self {
+        (file_id.volatile as u128) << 64 | file_id.persistent as u128
+    }
+}
+
 impl From<[u8; 16]> for FileId {
     fn from(data: [u8; 16]) -> Self {
-        let mut cursor = Cursor::new(data);
-        Self::read_le(&mut cursor).unwrap()
+        u128::from_le_bytes(data).into()
+    }
+}
+
+impl From<FileId> for [u8; 16] {
+    fn from(file_id: FileId) -> Self {
+        u128::from(file_id).to_le_bytes()
     }
 }
 
 impl From<Guid> for FileId {
     fn from(guid: Guid) -> Self {
-        let mut cursor = Cursor::new(Vec::new());
-        guid.write_le(&mut cursor).unwrap();
-        <Self as From<[u8; 16]>>::from(cursor.into_inner().try_into().unwrap())
+        <[u8; 16]>::from(guid).into()
     }
 }
 
@@ -116,12 +138,110 @@ pub struct CreateRequest {
     pub contexts: ChainedItemList<CreateContextRequest, 8>,
 }
 
+/// A builder for [`CreateRequest`]s. Use [`CreateRequest::builder`] to create one.
+///
+/// By default, the request opens an existing file ([`CreateDisposition::Open`]),
+/// with no oplock, impersonation level [`ImpersonationLevel::Impersonation`],
+/// no access, no sharing and no create contexts.
+#[derive(Debug)]
+pub struct CreateRequestBuilder {
+    request: CreateRequest,
+}
+
+impl CreateRequest {
+    /// Returns a builder for a create request of the file or pipe `name`.
+    pub fn builder(name: &str) -> CreateRequestBuilder {
+        CreateRequestBuilder {
+            request: CreateRequest {
+                requested_oplock_level: OplockLevel::None,
+                impersonation_level: ImpersonationLevel::Impersonation,
+                desired_access: FileAccessMask::new(),
+                file_attributes: FileAttributes::new(),
+                share_access: ShareAccessFlags::new(),
+                create_disposition: CreateDisposition::Open,
+                create_options: CreateOptions::new(),
+                name: name.into(),
+                contexts: ChainedItemList::default(),
+            },
+        }
+    }
+}
+
+impl CreateRequestBuilder {
+    /// Sets the requested oplock level.
+    pub fn oplock_level(mut self, oplock_level: OplockLevel) -> Self {
+        self.request.requested_oplock_level = oplock_level;
+        self
+    }
+
+    /// Sets the impersonation level.
+    pub fn impersonation_level(mut self, impersonation_level: ImpersonationLevel) -> Self {
+        self.request.impersonation_level = impersonation_level;
+        self
+    }
+
+    /// Sets the desired access.
+    pub fn desired_access(mut self, desired_access: FileAccessMask) -> Self {
+        self.request.desired_access = desired_access;
+        self
+    }
+
+    /// Sets the file attributes to apply when creating the file.
+    pub fn file_attributes(mut self, file_attributes: FileAttributes) -> Self {
+        self.request.file_attributes = file_attributes;
+        self
+    }
+
+    /// Sets the sharing mode.
+    pub fn share_access(mut self, share_access: ShareAccessFlags) -> Self {
+        self.request.share_access = share_access;
+        self
+    }
+
+    /// Sets the action to take if the file already exists, or does not exist.
+    pub fn disposition(mut self, create_disposition: CreateDisposition) -> Self {
+        self.request.create_disposition = create_disposition;
+        self
+    }
+
+    /// Sets the create options.
+    pub fn options(mut self, create_options: CreateOptions) -> Self {
+        self.request.create_options = create_options;
+        self
+    }
+
+    /// Appends a create context to the request.
+    pub fn context(mut self, context: impl Into<CreateContextRequest>) -> Self {
+        self.request.contexts.push(context.into());
+        self
+    }
+
+    /// Appends an [`AllocationSize`] context, to reserve `allocation_size` bytes for the created file.
+    pub fn allocation_size(self, allocation_size: u64) -> Self {
+        self.context(AllocationSize::new(allocation_size))
+    }
+
+    /// Requests a version 2 lease, optionally linked to the `parent` directory lease key.
+    ///
+    /// Appends a [`RequestLease::v2`] context, and sets the requested oplock level to [`OplockLevel::Lease`].
+    pub fn lease_v2(mut self, lease_key: u128, state: LeaseState, parent: Option<u128>) -> Self {
+        self.request.requested_oplock_level = OplockLevel::Lease;
+        self.context(RequestLease::v2(lease_key, state, parent))
+    }
+
+    /// Builds the request.
+    pub fn build(self) -> CreateRequest {
+        self.request
+    }
+}
+
 /// The impersonation level requested by the application issuing the create request.
 ///
 /// Reference: MS-SMB2 2.2.13
 #[smb_request_binrw]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, smb_dtyp::ReprEnum)]
 #[brw(repr(u32))]
+#[repr(u32)]
 pub enum ImpersonationLevel {
     /// The application-requested impersonation level is Anonymous
     Anonymous = 0x0,
@@ -138,8 +258,9 @@ pub enum ImpersonationLevel {
 ///
 /// Reference: MS-SMB2 2.2.13
 #[smb_request_binrw]
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone, Default, smb_dtyp::ReprEnum)]
 #[brw(repr(u32))]
+#[repr(u32)]
 pub enum CreateDisposition {
     /// If the file already exists, supersede it. Otherwise, create the file
     Superseded = 0x0,
@@ -156,6 +277,45 @@ pub enum CreateDisposition {
     OverwriteIf = 0x5,
 }
 
+impl CreateDisposition {
+    /// Returns a stable, lowercase label of the disposition.
+    pub const fn label(&self) -> &'static str {
+        match self {
+            CreateDisposition::Superseded => "superseded",
+            CreateDisposition::Open => "open",
+            CreateDisposition::Create => "create",
+            CreateDisposition::OpenIf => "open_if",
+            CreateDisposition::Overwrite => "overwrite",
+            CreateDisposition::OverwriteIf => "overwrite_if",
+        }
+    }
+}
+
+impl Display for CreateDisposition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+impl std::str::FromStr for CreateDisposition {
+    type Err = crate::SmbMsgError;
+
+    /// Parses a disposition from its [label][CreateDisposition::label].
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        [
+            CreateDisposition::Superseded,
+            CreateDisposition::Open,
+            CreateDisposition::Create,
+            CreateDisposition::OpenIf,
+            CreateDisposition::Overwrite,
+            CreateDisposition::OverwriteIf,
+        ]
+        .into_iter()
+        .find(|d| d.label() == s)
+        .ok_or_else(|| crate::SmbMsgError::InvalidData(format!("Unknown create disposition: {s}")))
+    }
+}
+
 /// Options to be applied when creating or opening the file.
 ///
 /// Reference: MS-SMB2 2.2.13
@@ -217,6 +377,47 @@ pub struct CreateOptions {
     __: B8,
 }
 
+impl CreateOptions {
+    /// Checks that no mutually-exclusive options are set together,
+    /// and that no options that the server must reject are set.
+    pub fn validate(&self) -> std::result::Result<(), CreateOptionsError> {
+        if self.directory_file() && self.non_directory_file() {
+            return Err(CreateOptionsError::DirectoryAndNonDirectoryFile);
+        }
+        if self.sequential_only() && self.random_access() {
+            return Err(CreateOptionsError::SequentialOnlyAndRandomAccess);
+        }
+        if self.open_by_file_id() {
+            return Err(CreateOptionsError::RejectedByServer("open_by_file_id"));
+        }
+        if self.reserve_opfilter() {
+            return Err(CreateOptionsError::RejectedByServer("reserve_opfilter"));
+        }
+        Ok(())
+    }
+
+    /// Clears all the options that should be set to 0 and are ignored by the server.
+    pub fn normalize(&mut self) {
+        self.set_synchronous_io_alert(false);
+        self.set_synchronous_io_nonalert(false);
+        self.set_complete_if_oplocked(false);
+        self.set_open_remote_instance(false);
+        self.set_open_requiring_oplock(false);
+        self.set_disallow_exclusive(false);
+    }
+}
+
+/// Errors returned by [`CreateOptions::validate`].
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum CreateOptionsError {
+    #[error("Both directory_file and non_directory_file options are set")]
+    DirectoryAndNonDirectoryFile,
+    #[error("Both sequential_only and random_access options are set")]
+    SequentialOnlyAndRandomAccess,
+    #[error("The {0} option is set, and the server must fail the request")]
+    RejectedByServer(&'static str),
+}
+
 /// Specifies the sharing mode for the open.
 ///
 /// Reference: MS-SMB2 2.2.13
@@ -271,13 +472,82 @@ pub struct CreateResponse {
     create_contexts_length: PosMarker<u32>, // bytes
 
     /// The list of create contexts returned in this response.
-    /// Use the [`CreateContextResponseData`]`::first_...` function family to get the first context of a specific type.
+    /// Use the [`CreateContextResponseData`]`::first_...` function family to get the first context of a specific type,
+    /// or the helpers such as [`CreateResponse::lease`] for the common ones.
     #[br(seek_before = SeekFrom::Start(create_contexts_offset.value as u64))]
     #[br(map_stream = |s| s.take_seek(create_contexts_length.value.into()))]
     #[bw(write_with = PosMarker::write_roff_size, args(&create_contexts_offset, &create_contexts_length))]
     pub create_contexts: ChainedItemList<CreateContextResponse, 8>,
 }
 
+impl CreateResponse {
+    /// Returns the first lease context in the response, if any.
+    pub fn lease(&self) -> Option<&RequestLease> {
+        CreateContextResponseData::first_rqls(&self.create_contexts)
+    }
+
+    /// Returns the first durable handle v2 context in the response, if any.
+    pub fn durable_v2(&self) -> Option<&DH2QResp> {
+        CreateContextResponseData::first_dh2q(&self.create_contexts)
+    }
+
+    /// Returns the first maximal access context in the response, if any.
+    pub fn maximal_access(&self) -> Option<&QueryMaximalAccessResponse> {
+        CreateContextResponseData::first_mxac(&self.create_contexts)
+    }
+
+    /// Returns an [`OpenHandle`] summarizing the open established by this response.
+    pub fn open_handle(&self) -> OpenHandle {
+        OpenHandle {
+            file_id: self.file_id,
+            oplock_level: self.oplock_level,
+            attributes: self.file_attributes,
+            end_of_file: self.endof_file,
+            allocation_size: self.allocation_size,
+            create_action: self.create_action,
+        }
+    }
+
+    /// Returns the first on-disk ID context in the response, if any.
+    pub fn on_disk_id(&self) -> Option<&QueryOnDiskIdResp> {
+        CreateContextResponseData::first_qfid(&self.create_contexts)
+    }
+
+    /// Returns true if the last portion of the opened path is a reparse point.
+    pub fn is_reparse_point(&self) -> bool {
+        self.flags.reparsepoint()
+    }
+
+    /// Returns the size, in bytes, of the file.
+    pub fn end_of_file(&self) -> u64 {
+        self.endof_file
+    }
+
+    /// Returns the size, in bytes, of the data that is allocated to the file.
+    pub fn allocation_size(&self) -> u64 {
+        self.allocation_size
+    }
+}
+
+/// A summary of the properties of an open, as returned in a [`CreateResponse`].
+///
+/// Use [`CreateResponse::open_handle`] to build one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpenHandle {
+    /// The identifier of the open
+    pub file_id: FileId,
+    /// The oplock level granted for this open
+    pub oplock_level: OplockLevel,
+    /// The attributes of the file
+    pub attributes: FileAttributes,
+    /// The size, in bytes, of the file
+    pub end_of_file: u64,
+    /// The size, in bytes, of the data that is allocated to the file
+    pub allocation_size: u64,
+    /// The action taken in establishing the open
+    pub create_action: CreateAction,
+}
+
 /// Response flags indicating properties of the opened file.
 /// Only valid for SMB 3.x dialect family.
 ///
@@ -294,7 +564,9 @@ pub struct CreateResponseFlags {
 ///
 /// Reference: MS-SMB2 2.2.14
 #[smb_response_binrw]
+#[derive(Clone, Copy, smb_dtyp::ReprEnum)]
 #[brw(repr(u32))]
+#[repr(u32)]
 pub enum CreateAction {
     /// An existing file was deleted and a new file was created in its place
     Superseded = 0x0,
@@ -306,6 +578,18 @@ pub enum CreateAction {
     Overwritten = 0x3,
 }
 
+impl Display for CreateAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            CreateAction::Superseded => "superseded",
+            CreateAction::Opened => "opened",
+            CreateAction::Created => "created",
+            CreateAction::Overwritten => "overwritten",
+        };
+        write!(f, "{label}")
+    }
+}
+
 macro_rules! create_context_half {
     (
         $struct_name:ident {
@@ -428,7 +712,8 @@ $(
                 [<CreateContext $struct_name Data>]::[<$context_type:camel $struct_name>](a) => Ok(a),
                 _ => Err(crate::SmbMsgError::UnexpectedContent {
                     expected: stringify!($req_type),
-                    actual: "", // self.data.name(), TODO: Fix this by making name() a string.
+                    actual: CreateContextType::from_name(self.data.name())
+                        .map_or("Unknown", |t| t.str_name()),
                 }),
             }
         }
@@ -443,7 +728,7 @@ macro_rules! make_create_context {
     (
         $(
             $(#[doc = $docstring:literal])*
-            $context_type:ident : $class_name:literal, $req_type:ty $(, $res_type:ty)?;
+            $context_type:ident : $class_name:literal => $str_name:literal, $req_type:ty $(, $res_type:ty)?;
         )+
     ) => {
         pastey::paste!{
@@ -478,6 +763,16 @@ impl CreateContextType {
             )+
         }
     }
+
+    /// Returns a human-readable label of the create context,
+    /// to be used in logs and error messages.
+    pub const fn str_name(&self) -> &'static str {
+        match self {
+            $(
+                Self::[<$context_type:upper>] => $str_name,
+            )+
+        }
+    }
 }
         }
 
@@ -497,36 +792,49 @@ impl CreateContextType {
 
 make_create_context!(
     /// The data contains the extended attributes that MUST be stored on the created file.
-    exta: b"ExtA", ChainedItemList<FileFullEaInformation>;
+    exta: b"ExtA" => "ExtA", ChainedItemList<FileFullEaInformation>;
     /// The data contains a security descriptor that MUST be stored on the created file.
-    secd: b"SecD", SecurityDescriptor;
+    secd: b"SecD" => "SecD", SecurityDescriptor;
     /// The client is requesting the open to be durable
-    dhnq: b"DHnQ", DurableHandleRequest, DurableHandleResponse;
+    dhnq: b"DHnQ" => "DHnQ", DurableHandleRequest, DurableHandleResponse;
     /// The client is requesting to reconnect to a durable open after being disconnected
-    dhnc: b"DHNc", DurableHandleReconnect;
+    dhnc: b"DHNc" => "DHNc", DurableHandleReconnect;
     /// The data contains the required allocation size of the newly created file.
-    alsi: b"AlSi", AllocationSize;
+    alsi: b"AlSi" => "AlSi", AllocationSize;
     /// The client is requesting that the server return maximal access information.
-    mxac: b"MxAc", QueryMaximalAccessRequest, QueryMaximalAccessResponse;
+    mxac: b"MxAc" => "MxAc", QueryMaximalAccessRequest, QueryMaximalAccessResponse;
     /// The client is requesting that the server open an earlier version of the file identified by the provided time stamp.
-    twrp: b"TWrp", TimewarpToken;
+    twrp: b"TWrp" => "TWrp", TimewarpToken;
     /// The client is requesting that the server return a 32-byte opaque BLOB that uniquely identifies the file being opened on disk.
-    qfid: b"QFid", QueryOnDiskIdReq, QueryOnDiskIdResp;
+    qfid: b"QFid" => "QFid", QueryOnDiskIdReq, QueryOnDiskIdResp;
     /// The client is requesting that the server return a lease. This value is only supported for the SMB 2.1 and 3.x dialect family.
-    rqls: b"RqLs", RequestLease, RequestLease; // v1+2, request & response are the same
+    rqls: b"RqLs" => "RqLs", RequestLease, RequestLease; // v1+2, request & response are the same
     /// The client is requesting the open to be durable. This value is only supported for the SMB 3.x dialect family.
-    dh2q: b"DH2Q", DurableHandleRequestV2, DH2QResp;
+    dh2q: b"DH2Q" => "DH2Q", DurableHandleRequestV2, DH2QResp;
     /// The client is requesting to reconnect to a durable open after being disconnected. This value is only supported for the SMB 3.x dialect family.
-    dh2c: b"DH2C", DurableHandleReconnectV2;
+    dh2c: b"DH2C" => "DH2C", DurableHandleReconnectV2;
     /// The client is supplying an identifier provided by an application instance while opening a file. This value is only supported for the SMB 3.x dialect family.
-    appinstid: b"\x45\xBC\xA6\x6A\xEF\xA7\xF7\x4A\x90\x08\xFA\x46\x2E\x14\x4D\x74", AppInstanceId, AppInstanceId;
+    appinstid: b"\x45\xBC\xA6\x6A\xEF\xA7\xF7\x4A\x90\x08\xFA\x46\x2E\x14\x4D\x74" => "AppInstanceId", AppInstanceId, AppInstanceId;
     /// The client is supplying a version to correspond to the application instance identifier.  This value is only supported for SMB 3.1.1 dialect.
-    appinstver: b"\xB9\x82\xD0\xB7\x3B\x56\x07\x4F\xA0\x7B\x52\x4A\x81\x16\xA0\x10", AppInstanceVersion, AppInstanceVersion;
+    appinstver: b"\xB9\x82\xD0\xB7\x3B\x56\x07\x4F\xA0\x7B\x52\x4A\x81\x16\xA0\x10" => "AppInstanceVersion", AppInstanceVersion, AppInstanceVersion;
     /// Provided by an application while opening a shared virtual disk file.
     /// This Create Context value is not valid for the SMB 2.002, SMB 2.1, and SMB 3.0 dialects
-    svhdxopendev: b"\x9C\xCB\xCF\x9E\x04\xC1\xE6\x43\x98\x0E\x15\x8D\xA1\xF6\xEC\x83", SvhdxOpenDeviceContext, SvhdxOpenDeviceContext;
+    svhdxopendev: b"\x9C\xCB\xCF\x9E\x04\xC1\xE6\x43\x98\x0E\x15\x8D\xA1\xF6\xEC\x83" => "SvhdxOpenDevice", SvhdxOpenDeviceContext, SvhdxOpenDeviceContext;
 );
 
+impl CreateContextRequest {
+    /// Serializes this context on its own, framed as the last (and only) item of a create context list:
+    /// the next entry offset is 0, and no trailing padding is written.
+    ///
+    /// The result matches the bytes of this context when it is the last context of a [`CreateRequest`].
+    #[cfg(feature = "client")]
+    pub fn to_bytes_standalone(&self) -> crate::Result<Vec<u8>> {
+        let mut cursor = Cursor::new(Vec::new());
+        ChainedItemList::<_, 8>::from(vec![self]).write_le(&mut cursor)?;
+        Ok(cursor.into_inner())
+    }
+}
+
 /// Request for a durable handle that can survive brief network disconnections.
 ///
 /// Reference: MS-SMB2 2.2.13.2.3
@@ -571,6 +879,13 @@ pub struct AllocationSize {
     pub allocation_size: u64,
 }
 
+impl AllocationSize {
+    /// Creates a new allocation size context, reserving `allocation_size` bytes.
+    pub fn new(allocation_size: u64) -> Self {
+        Self { allocation_size }
+    }
+}
+
 /// Request to open a version of the file at a previous point in time.
 ///
 /// Reference: MS-SMB2 2.2.13.2.7
@@ -590,6 +905,50 @@ pub enum RequestLease {
     RqLsReqv2(RequestLeaseV2),
 }
 
+impl RequestLease {
+    /// Creates a version 1 lease request (SMB 2.1 and 3.x dialect family).
+    pub fn v1(lease_key: u128, state: LeaseState) -> Self {
+        RequestLease::RqLsReqv1(RequestLeaseV1 {
+            lease_key,
+            lease_state: state,
+        })
+    }
+
+    /// Creates a version 2 lease request (SMB 3.x dialect family only).
+    ///
+    /// If `parent` is provided, the parent lease key is set, along with
+    /// the [`LeaseFlags::parent_lease_key_set`] flag.
+    pub fn v2(lease_key: u128, state: LeaseState, parent: Option<u128>) -> Self {
+        let lease = RequestLeaseV2 {
+            lease_key,
+            lease_state: state,
+            lease_flags: LeaseFlags::new(),
+            parent_lease_key: 0,
+            epoch: 0,
+        };
+        RequestLease::RqLsReqv2(match parent {
+            Some(parent) => lease.with_parent(parent),
+            None => lease,
+        })
+    }
+
+    /// Returns the lease key, regardless of the lease version.
+    pub fn lease_key(&self) -> u128 {
+        match self {
+            RequestLease::RqLsReqv1(v1) => v1.lease_key,
+            RequestLease::RqLsReqv2(v2) => v2.lease_key,
+        }
+    }
+
+    /// Returns the lease state, regardless of the lease version.
+    pub fn lease_state(&self) -> LeaseState {
+        match self {
+            RequestLease::RqLsReqv1(v1) => v1.lease_state,
+            RequestLease::RqLsReqv2(v2) => v2.lease_state,
+        }
+    }
+}
+
 /// Version 1 lease request and response (SMB 2.1 and 3.x dialect family).
 /// Contains the lease key, state, flags, and duration.
 ///
@@ -630,6 +989,15 @@ pub struct RequestLeaseV2 {
     reserved: u16,
 }
 
+impl RequestLeaseV2 {
+    /// Sets the parent lease key, along with the [`LeaseFlags::parent_lease_key_set`] flag.
+    pub fn with_parent(mut self, parent_lease_key: u128) -> Self {
+        self.parent_lease_key = parent_lease_key;
+        self.lease_flags.set_parent_lease_key_set(true);
+        self
+    }
+}
+
 /// Flags for lease requests and responses.
 ///
 /// Reference: MS-SMB2 2.2.13.2.10, 2.2.14.2.10, 2.2.14.2.11
@@ -682,11 +1050,71 @@ pub struct DurableHandleV2Flags {
 #[smb_request_binrw]
 pub struct DurableHandleReconnectV2 {
     /// The file ID for the open that is being reestablished
-    file_id: FileId,
+    pub file_id: FileId,
     /// Unique ID that identifies the create request
-    create_guid: Guid,
+    pub create_guid: Guid,
     /// Flags indicating whether a persistent handle is requested
-    flags: DurableHandleV2Flags,
+    pub flags: DurableHandleV2Flags,
+}
+
+impl DurableHandleReconnectV2 {
+    /// Creates a reconnect context for the open `file_id`,
+    /// which was created with `create_guid`.
+    pub fn new(file_id: FileId, create_guid: Guid, flags: DurableHandleV2Flags) -> Self {
+        Self {
+            file_id,
+            create_guid,
+            flags,
+        }
+    }
+}
+
+/// The state of a durable (v2) open, required to reconnect to it after the connection is lost.
+///
+/// Use [`HandleState::to_reconnect_v2`] to build the reconnect context,
+/// which must carry the same create GUID that was sent in the original [`DurableHandleRequestV2`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandleState {
+    /// The file ID of the durable open
+    pub file_id: FileId,
+    /// The create GUID sent in the original [`DurableHandleRequestV2`]
+    pub create_guid: Guid,
+    /// Whether the server granted a persistent handle
+    pub persistent: bool,
+}
+
+impl HandleState {
+    /// Builds the handle state from a create response, and the create GUID sent in its request.
+    ///
+    /// Returns `None` if the server did not grant a durable v2 handle.
+    pub fn from_create_response(response: &CreateResponse, create_guid: Guid) -> Option<Self> {
+        let durable = response.durable_v2()?;
+        Some(Self {
+            file_id: response.file_id,
+            create_guid,
+            persistent: durable.flags.persistent(),
+        })
+    }
+
+    /// Returns the reconnect context for this open.
+    pub fn to_reconnect_v2(&self) -> DurableHandleReconnectV2 {
+        DurableHandleReconnectV2::new(
+            self.file_id,
+            self.create_guid,
+            DurableHandleV2Flags::new().with_persistent(self.persistent),
+        )
+    }
+
+    /// Returns true if `response` reestablished the open described by this state.
+    ///
+    /// The create GUID is not returned by the server, so this checks that the persistent
+    /// part of the file ID matches, and that the granted persistence, if returned, matches.
+    pub fn matches(&self, response: &CreateResponse) -> bool {
+        response.file_id.persistent == self.file_id.persistent
+            && response
+                .durable_v2()
+                .is_none_or(|durable| durable.flags.persistent() == self.persistent)
+    }
 }
 
 /// Application instance identifier (SMB 3.x dialect family only).
@@ -761,6 +1189,59 @@ pub struct SvhdxOpenDeviceContextV2 {
     pub virtual_size: u64,
 }
 
+impl SvhdxOpenDeviceContext {
+    /// Returns the decoded initiator host name, regardless of the context version.
+    pub fn initiator_host_name(&self) -> String {
+        match self {
+            SvhdxOpenDeviceContext::V1(v1) => v1.initiator_host_name(),
+            SvhdxOpenDeviceContext::V2(v2) => v2.initiator_host_name(),
+        }
+    }
+}
+
+macro_rules! svhdx_initiator_host_name_impl {
+    ($($struct_name:ident),+) => {
+        $(
+impl $struct_name {
+    /// The maximum length of the initiator host name, in UTF-16 code units.
+    pub const MAX_INITIATOR_HOST_NAME_CHARS: usize = 126 / 2;
+
+    /// Decodes the initiator host name, using the first
+    /// [`initiator_host_name_length`][Self::initiator_host_name_length] bytes
+    /// of the [`initiator_host_name`][Self::initiator_host_name] field.
+    ///
+    /// A trailing odd byte in the length is ignored, and invalid UTF-16 is replaced lossily.
+    pub fn initiator_host_name(&self) -> String {
+        let chars = (self.initiator_host_name_length as usize / 2)
+            .min(Self::MAX_INITIATOR_HOST_NAME_CHARS);
+        String::from_utf16_lossy(&self.initiator_host_name[..chars])
+    }
+
+    /// Sets the initiator host name, filling the rest of the fixed buffer with zeros,
+    /// and updating the [`initiator_host_name_length`][Self::initiator_host_name_length] field.
+    ///
+    /// Returns an error if the name is longer than [`Self::MAX_INITIATOR_HOST_NAME_CHARS`] UTF-16 code units.
+    pub fn set_initiator_host_name(&mut self, name: &str) -> crate::Result<()> {
+        let wide: Vec<u16> = name.encode_utf16().collect();
+        if wide.len() > Self::MAX_INITIATOR_HOST_NAME_CHARS {
+            return Err(crate::SmbMsgError::InvalidData(format!(
+                "Initiator host name is too long: {} UTF-16 code units (max {})",
+                wide.len(),
+                Self::MAX_INITIATOR_HOST_NAME_CHARS
+            )));
+        }
+        self.initiator_host_name = [0; 126 / 2];
+        self.initiator_host_name[..wide.len()].copy_from_slice(&wide);
+        self.initiator_host_name_length = (wide.len() * 2) as u16;
+        Ok(())
+    }
+}
+        )+
+    };
+}
+
+svhdx_initiator_host_name_impl!(SvhdxOpenDeviceContextV1, SvhdxOpenDeviceContextV2);
+
 #[smb_response_binrw]
 pub struct QueryMaximalAccessResponse {
     // MS-SMB2, 2.2.14.2.5: "MaximalAccess field is valid only if QueryStatus is STATUS_SUCCESS.
@@ -788,6 +1269,34 @@ impl QueryMaximalAccessResponse {
             None
         }
     }
+
+    /// Returns the maximal access mask if the query was successful,
+    /// or the status returned by the server otherwise.
+    pub fn result(&self) -> std::result::Result<FileAccessMask, Status> {
+        if self.is_success() {
+            Ok(self.maximal_access)
+        } else {
+            Err(self.query_status)
+        }
+    }
+
+    /// Returns true if the query was successful, and all the rights in `desired` are granted by the maximal access.
+    ///
+    /// Generic rights in `desired` are mapped to their file-specific rights (see [`FileAccessMask::map_generic`]),
+    /// and the `maximum_allowed` bit is ignored.
+    pub fn grants(&self, desired: FileAccessMask) -> bool {
+        let Some(granted) = self.maximal_access() else {
+            return false;
+        };
+        let granted = u32::from_le_bytes(granted.map_generic().into_bytes());
+        let desired = u32::from_le_bytes(
+            desired
+                .map_generic()
+                .with_maximum_allowed(false)
+                .into_bytes(),
+        );
+        desired & !granted == 0
+    }
 }
 
 /// Response containing disk file and volume identifiers for the opened file.
@@ -870,6 +1379,11 @@ mod tests {
 
     use super::*;
 
+    const CREATE_REQUEST_DATA: &str = "390000000200000000000000000000000000000000000000810010000000000007000000010000002000020078000a008800000068
+    000000680065006c006c006f0000000000000038000000100004000000180020000000444832510000000000000000000000000000000000
+    00000020a379c6a0c0ef118b7b000c29801682180000001000040000001800000000004d7841630000000000000000100004000000180000
+    0000005146696400000000";
+
     test_request! {
         Create {
             requested_oplock_level: OplockLevel::None,
@@ -896,10 +1410,32 @@ mod tests {
                 QueryOnDiskIdReq.into(),
             ]
             .into(),
-        } => "390000000200000000000000000000000000000000000000810010000000000007000000010000002000020078000a008800000068
-        000000680065006c006c006f0000000000000038000000100004000000180020000000444832510000000000000000000000000000000000
-        00000020a379c6a0c0ef118b7b000c29801682180000001000040000001800000000004d7841630000000000000000100004000000180000
-        0000005146696400000000"
+        } => CREATE_REQUEST_DATA
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_create_context_to_bytes_standalone() {
+        let ctx: CreateContextRequest = DurableHandleRequestV2 {
+            timeout: 0,
+            flags: DurableHandleV2Flags::new(),
+            create_guid: 0x821680290c007b8b11efc0a0c679a320u128.to_le_bytes().into(),
+        }
+        .into();
+        let bytes = ctx.to_bytes_standalone().unwrap();
+
+        // The DH2Q context is the first context of the create request fixture above.
+        let fixture = smb_tests::hex_to_u8_array! {CREATE_REQUEST_DATA};
+        let contexts_offset =
+            u32::from_le_bytes(fixture[48..52].try_into().unwrap()) as usize - Header::STRUCT_SIZE;
+        let chained = &fixture[contexts_offset..contexts_offset + bytes.len()];
+        // Standalone, the next entry offset is 0 instead of the offset of the next context.
+        assert_eq!(
+            u32::from_le_bytes(chained[0..4].try_into().unwrap()) as usize,
+            bytes.len()
+        );
+        assert_eq!(bytes[0..4], [0; 4]);
+        assert_eq!(bytes[4..], chained[4..]);
     }
 
     test_response! {
@@ -968,6 +1504,127 @@ mod tests {
         0000000000000000000000000000"
     }
 
+    /// An empty, opened [`CreateResponse`], for tests to override.
+    fn create_response_for_test() -> CreateResponse {
+        CreateResponse {
+            oplock_level: OplockLevel::None,
+            flags: CreateResponseFlags::new(),
+            create_action: CreateAction::Opened,
+            creation_time: FileTime::ZERO,
+            last_access_time: FileTime::ZERO,
+            last_write_time: FileTime::ZERO,
+            change_time: FileTime::ZERO,
+            allocation_size: 0,
+            endof_file: 0,
+            file_attributes: FileAttributes::new(),
+            file_id: FileId::EMPTY,
+            create_contexts: vec![].into(),
+        }
+    }
+
+    #[test]
+    fn test_create_response_context_helpers() {
+        let response = CreateResponse {
+            create_contexts: vec![
+                QueryMaximalAccessResponse {
+                    query_status: Status::Success,
+                    maximal_access: FileAccessMask::from_bytes(0x001f01ffu32.to_le_bytes()),
+                }
+                .into(),
+                RequestLease::v1(1, LeaseState::new().with_read_caching(true)).into(),
+            ]
+            .into(),
+            ..create_response_for_test()
+        };
+        assert_eq!(response.lease().unwrap().lease_key(), 1);
+        assert!(response.maximal_access().unwrap().is_success());
+        assert!(response.durable_v2().is_none());
+        assert!(response.on_disk_id().is_none());
+    }
+
+    #[test]
+    fn test_handle_state_reconnect() {
+        let create_guid = guid!("5a08e844-45c3-234d-87c6-596d2bc8bca5");
+        let file_id = FileId {
+            persistent: 0x1234,
+            volatile: 0x5678,
+        };
+        let response = CreateResponse {
+            create_action: CreateAction::Created,
+            file_id,
+            create_contexts: vec![
+                DH2QResp {
+                    timeout: 180000,
+                    flags: DurableHandleV2Flags::new().with_persistent(true),
+                }
+                .into(),
+            ]
+            .into(),
+            ..create_response_for_test()
+        };
+
+        let state = HandleState::from_create_response(&response, create_guid).unwrap();
+        assert!(state.persistent);
+        assert!(state.matches(&response));
+        assert_eq!(
+            state.to_reconnect_v2(),
+            DurableHandleReconnectV2 {
+                file_id,
+                create_guid,
+                flags: DurableHandleV2Flags::new().with_persistent(true),
+            }
+        );
+
+        let other = CreateResponse {
+            file_id: FileId::FULL,
+            create_contexts: vec![].into(),
+            ..response
+        };
+        assert!(!state.matches(&other));
+        assert!(HandleState::from_create_response(&other, create_guid).is_none());
+    }
+
+    #[test]
+    fn test_create_response_open_handle() {
+        let response = CreateResponse {
+            allocation_size: 4096,
+            file_attributes: FileAttributes::new().with_normal(true),
+            file_id: guid!("00000001-0001-0000-0100-000001000000").into(),
+            ..create_response_for_test()
+        };
+        assert_eq!(
+            response.open_handle(),
+            OpenHandle {
+                file_id: guid!("00000001-0001-0000-0100-000001000000").into(),
+                oplock_level: OplockLevel::None,
+                attributes: FileAttributes::new().with_normal(true),
+                end_of_file: 0,
+                allocation_size: 4096,
+                create_action: CreateAction::Opened,
+            }
+        );
+    }
+
+    #[test]
+    fn test_create_response_reparse_point() {
+        let response = CreateResponse {
+            flags: CreateResponseFlags::new().with_reparsepoint(true),
+            allocation_size: 8192,
+            endof_file: 5000,
+            file_attributes: FileAttributes::new().with_reparse_point(true),
+            ..create_response_for_test()
+        };
+        assert!(response.is_reparse_point());
+        assert_eq!(response.end_of_file(), 5000);
+        assert_eq!(response.allocation_size(), 8192);
+
+        let response = CreateResponse {
+            flags: CreateResponseFlags::new(),
+            ..response
+        };
+        assert!(!response.is_reparse_point());
+    }
+
     /*
     Tests to add for contexts:
     dhnc: b"DHNc", DurableHandleReconnect, DurableHandleReconnect,
@@ -1016,7 +1673,13 @@ mod tests {
         } => "000400000001e72a 00000000b017cfd9 00000000000000000000000000000000"
     }
 
-    // TODO(TEST): RqLsV1
+    test_binrw_request! {
+        RequestLease => rqlsv1: RequestLease::v1(
+            guid!("b69d8fd8-184b-7c4d-a359-40c8a53cd2b7").as_u128(),
+            LeaseState::new().with_read_caching(true).with_handle_caching(true),
+        ) => "d88f9db64b184d7ca35940c8a53cd2b703000000000000000000000000000000"
+    }
+
     test_binrw_request! {
         RequestLease => rqlsv2: RequestLease::RqLsReqv2(RequestLeaseV2 {
             lease_key: guid!("b69d8fd8-184b-7c4d-a359-40c8a53cd2b7").as_u128(),
@@ -1027,6 +1690,110 @@ mod tests {
         }) => "d88f9db64b184d7ca35940c8a53cd2b703000000040000000000000000000000a38e152ddb5549f79cd1095496a0662700000000"
     }
 
+    test_binrw_request! {
+        RequestLease => rqlsv2_with_parent: RequestLease::RqLsReqv2(RequestLeaseV2 {
+            lease_key: guid!("b69d8fd8-184b-7c4d-a359-40c8a53cd2b7").as_u128(),
+            lease_state: LeaseState::new().with_read_caching(true).with_handle_caching(true),
+            lease_flags: LeaseFlags::new(),
+            parent_lease_key: 0,
+            epoch: 0
+        }.with_parent(guid!("2d158ea3-55db-f749-9cd1-095496a06627").as_u128()))
+        => "d88f9db64b184d7ca35940c8a53cd2b703000000040000000000000000000000a38e152ddb5549f79cd1095496a0662700000000"
+    }
+
+    #[test]
+    fn test_request_lease_constructors() {
+        let key = guid!("b69d8fd8-184b-7c4d-a359-40c8a53cd2b7").as_u128();
+        let parent = guid!("2d158ea3-55db-f749-9cd1-095496a06627").as_u128();
+        let state = LeaseState::new()
+            .with_read_caching(true)
+            .with_handle_caching(true);
+
+        let v2 = RequestLease::v2(key, state, Some(parent));
+        assert_eq!(
+            v2,
+            RequestLease::RqLsReqv2(RequestLeaseV2 {
+                lease_key: key,
+                lease_state: state,
+                lease_flags: LeaseFlags::new().with_parent_lease_key_set(true),
+                parent_lease_key: parent,
+                epoch: 0
+            })
+        );
+        assert_eq!(v2.lease_key(), key);
+        assert_eq!(v2.lease_state(), state);
+
+        match RequestLease::v2(key, state, None) {
+            RequestLease::RqLsReqv2(v2) => {
+                assert!(!v2.lease_flags.parent_lease_key_set());
+                assert_eq!(v2.parent_lease_key, 0);
+            }
+            _ => panic!("Expected a v2 lease"),
+        }
+
+        let v1 = RequestLease::v1(key, state);
+        assert_eq!(v1.lease_key(), key);
+        assert_eq!(v1.lease_state(), state);
+    }
+
+    #[cfg(all(feature = "client", feature = "server"))]
+    #[test]
+    fn test_create_request_builder_allocation_size() {
+        use std::io::Cursor;
+
+        let request = CreateRequest::builder("new.bin")
+            .desired_access(FileAccessMask::new().with_generic_write(true))
+            .disposition(CreateDisposition::Create)
+            .allocation_size(0x100000)
+            .build();
+        assert_eq!(
+            CreateContextRequestData::first_alsi(&request.contexts),
+            Some(&AllocationSize::new(0x100000))
+        );
+
+        let mut cursor = Cursor::new(Vec::new());
+        request.write_le(&mut cursor).unwrap();
+        cursor.set_position(0);
+        let read = CreateRequest::read_le(&mut cursor).unwrap();
+        assert_eq!(read, request);
+        assert_eq!(
+            CreateContextRequestData::first_alsi(&read.contexts).map(|a| a.allocation_size),
+            Some(0x100000)
+        );
+    }
+
+    #[cfg(all(feature = "client", feature = "server"))]
+    #[test]
+    fn test_create_request_builder_lease_v2() {
+        use std::io::Cursor;
+
+        let request = CreateRequest::builder("dir\\child.txt")
+            .lease_v2(
+                guid!("b69d8fd8-184b-7c4d-a359-40c8a53cd2b7").as_u128(),
+                LeaseState::new()
+                    .with_read_caching(true)
+                    .with_handle_caching(true),
+                Some(guid!("2d158ea3-55db-f749-9cd1-095496a06627").as_u128()),
+            )
+            .build();
+        assert_eq!(request.requested_oplock_level, OplockLevel::Lease);
+
+        let mut cursor = Cursor::new(Vec::new());
+        request.write_le(&mut cursor).unwrap();
+        cursor.set_position(0);
+        let read = CreateRequest::read_le(&mut cursor).unwrap();
+        assert_eq!(read, request);
+
+        // Same layout as the `rqlsv2` fixture.
+        let lease = CreateContextRequestData::first_rqls(&read.contexts).unwrap();
+        let mut cursor = Cursor::new(Vec::new());
+        lease.write_le(&mut cursor).unwrap();
+        assert_eq!(
+            cursor.into_inner(),
+            smb_tests::hex_to_u8_array! {"d88f9db64b184d7ca35940c8a53cd2b703000000040000000000000000000000a38e152ddb5549f79cd1095496a0662700000000"}
+        );
+    }
+
     test_binrw_request! {
         struct AllocationSize {
             allocation_size: 0xebfef0d4c000,
@@ -1061,4 +1828,201 @@ mod tests {
             flags: DurableHandleV2Flags::new(),
         } => "b300000008000000dd000000080000008c423ea2ac1b437e845191f9f2277a9500000000"
     }
+
+    #[test]
+    fn test_durable_handle_reconnect_v2_new() {
+        let file_id: FileId = guid!("000000b3-0008-0000-dd00-000008000000").into();
+        let create_guid = guid!("a23e428c-1bac-7e43-8451-91f9f2277a95");
+        let ctx = DurableHandleReconnectV2::new(
+            file_id,
+            create_guid,
+            DurableHandleV2Flags::new().with_persistent(true),
+        );
+        assert_eq!(ctx.file_id, file_id);
+        assert_eq!(ctx.create_guid, create_guid);
+        assert!(ctx.flags.persistent());
+    }
+
+    #[test]
+    fn test_context_try_into_mismatch_names_actual() {
+        let ctx: CreateContextRequest = QueryMaximalAccessRequest::default().into();
+        let res: crate::Result<DurableHandleRequest> = ctx.try_into();
+        match res {
+            Err(SmbMsgError::UnexpectedContent { actual, expected }) => {
+                assert_eq!(actual, "MxAc");
+                assert_eq!(expected, "DurableHandleRequest");
+            }
+            _ => panic!("Expected UnexpectedContent error"),
+        }
+        assert_eq!(CreateContextType::APPINSTID.str_name(), "AppInstanceId");
+    }
+
+    #[test]
+    fn test_svhdx_initiator_host_name() {
+        let mut ctx = SvhdxOpenDeviceContextV1 {
+            version: 1,
+            has_initiator_id: false.into(),
+            initiator_id: Guid::ZERO,
+            flags: 0,
+            originator_flags: 0,
+            open_request_id: 0,
+            initiator_host_name_length: 0,
+            initiator_host_name: [0xffff; 126 / 2],
+        };
+        assert_eq!(ctx.initiator_host_name(), "");
+
+        ctx.set_initiator_host_name("node-1").unwrap();
+        assert_eq!(ctx.initiator_host_name_length, 12);
+        assert_eq!(ctx.initiator_host_name[6..], [0; 126 / 2 - 6]);
+        assert_eq!(ctx.initiator_host_name(), "node-1");
+
+        // Odd byte length - trailing byte is ignored.
+        ctx.initiator_host_name_length = 11;
+        assert_eq!(ctx.initiator_host_name(), "node-");
+
+        let too_long = "a".repeat(SvhdxOpenDeviceContextV1::MAX_INITIATOR_HOST_NAME_CHARS + 1);
+        assert!(ctx.set_initiator_host_name(&too_long).is_err());
+        let max = "a".repeat(SvhdxOpenDeviceContextV1::MAX_INITIATOR_HOST_NAME_CHARS);
+        ctx.set_initiator_host_name(&max).unwrap();
+        assert_eq!(SvhdxOpenDeviceContext::V1(ctx).initiator_host_name(), max);
+    }
+
+    #[test]
+    fn test_create_options_validate() {
+        assert_eq!(CreateOptions::new().validate(), Ok(()));
+        assert_eq!(
+            CreateOptions::new()
+                .with_directory_file(true)
+                .with_non_directory_file(true)
+                .validate(),
+            Err(CreateOptionsError::DirectoryAndNonDirectoryFile)
+        );
+        assert_eq!(
+            CreateOptions::new()
+                .with_sequential_only(true)
+                .with_random_access(true)
+                .validate(),
+            Err(CreateOptionsError::SequentialOnlyAndRandomAccess)
+        );
+        assert_eq!(
+            CreateOptions::new().with_open_by_file_id(true).validate(),
+            Err(CreateOptionsError::RejectedByServer("open_by_file_id"))
+        );
+        assert_eq!(
+            CreateOptions::new().with_reserve_opfilter(true).validate(),
+            Err(CreateOptionsError::RejectedByServer("reserve_opfilter"))
+        );
+    }
+
+    #[test]
+    fn test_create_options_normalize() {
+        let mut options = CreateOptions::new()
+            .with_non_directory_file(true)
+            .with_synchronous_io_nonalert(true)
+            .with_disallow_exclusive(true)
+            .with_open_requiring_oplock(true);
+        options.normalize();
+        assert_eq!(options, CreateOptions::new().with_non_directory_file(true));
+    }
+
+    #[test]
+    fn test_create_disposition_labels() {
+        use std::str::FromStr;
+
+        for disposition in [
+            CreateDisposition::Superseded,
+            CreateDisposition::Open,
+            CreateDisposition::Create,
+            CreateDisposition::OpenIf,
+            CreateDisposition::Overwrite,
+            CreateDisposition::OverwriteIf,
+        ] {
+            let label = disposition.to_string();
+            assert_eq!(CreateDisposition::from_str(&label).unwrap(), disposition);
+        }
+        assert_eq!(CreateDisposition::OpenIf.to_string(), "open_if");
+        assert!(CreateDisposition::from_str("OpenIf").is_err());
+
+        assert_eq!(CreateAction::Overwritten.to_string(), "overwritten");
+        assert_eq!(CreateAction::Created.to_string(), "created");
+    }
+
+    #[test]
+    fn test_file_id_guid_roundtrip() {
+        let guid = guid!("000000b3-0008-0000-dd00-000008000000");
+        let file_id = FileId::from(guid);
+        assert_eq!(
+            file_id,
+            FileId {
+                persistent: 0x00000008000000b3,
+                volatile: 0x00000008000000dd,
+            }
+        );
+        let file_id_bytes: [u8; 16] = file_id.into();
+        assert_eq!(file_id_bytes, <[u8; 16]>::from(guid));
+        assert_eq!(Guid::from(file_id_bytes), guid);
+    }
+
+    #[test]
+    fn test_file_id_u128_roundtrip() {
+        const VALUE: u128 = 0x00000008000000dd_00000008000000b3;
+        let file_id = FileId::from(VALUE);
+        assert_eq!(
+            file_id,
+            FileId {
+                persistent: 0x00000008000000b3,
+                volatile: 0x00000008000000dd,
+            }
+        );
+        assert_eq!(u128::from(file_id), VALUE);
+        assert_eq!(<[u8; 16]>::from(file_id), VALUE.to_le_bytes());
+        assert_eq!(u128::from(FileId::FULL), u128::MAX);
+        assert_eq!(u128::from(FileId::EMPTY), 0);
+    }
+
+    #[test]
+    fn test_maximal_access_grants() {
+        let full = QueryMaximalAccessResponse {
+            query_status: Status::Success,
+            maximal_access: FileAccessMask::from_bytes(0x001f01ffu32.to_le_bytes()),
+        };
+        assert!(full.grants(FileAccessMask::new().with_generic_read(true)));
+        assert!(full.grants(FileAccessMask::new().with_generic_all(true)));
+        assert!(full.grants(FileAccessMask::new().with_write_owner(true)));
+        assert!(full.grants(FileAccessMask::new().with_maximum_allowed(true)));
+        assert!(!full.grants(FileAccessMask::new().with_access_system_security(true)));
+
+        // FILE_GENERIC_READ | FILE_GENERIC_EXECUTE
+        let read_execute = QueryMaximalAccessResponse {
+            query_status: Status::Success,
+            maximal_access: FileAccessMask::from_bytes(0x001200a9u32.to_le_bytes()),
+        };
+        assert!(read_execute.grants(FileAccessMask::new().with_generic_read(true)));
+        assert!(read_execute.grants(FileAccessMask::new().with_file_execute(true)));
+        assert!(!read_execute.grants(FileAccessMask::new().with_write_owner(true)));
+        assert!(!read_execute.grants(FileAccessMask::new().with_generic_write(true)));
+
+        let failed = QueryMaximalAccessResponse {
+            query_status: Status::AccessDenied,
+            ..full
+        };
+        assert!(!failed.grants(FileAccessMask::new()));
+    }
+
+    #[test]
+    fn test_maximal_access_result() {
+        let mask = FileAccessMask::from_bytes(0x001f01ffu32.to_le_bytes());
+        let ok = QueryMaximalAccessResponse {
+            query_status: Status::Success,
+            maximal_access: mask,
+        };
+        assert_eq!(ok.result(), Ok(mask));
+
+        let not_mapped = QueryMaximalAccessResponse {
+            query_status: Status::NotMapped,
+            maximal_access: FileAccessMask::default(),
+        };
+        assert_eq!(not_mapped.result(), Err(Status::NotMapped));
+        assert_eq!(not_mapped.maximal_access(), None);
+    }
 }
@@ -23,6 +23,8 @@ pub struct ReqGetDfsReferral {
 /// See [`ReqGetDfsReferral::max_referral_level`].
 #[smb_request_binrw]
 #[brw(repr(u16))]
+#[repr(u16)]
+#[derive(smb_dtyp::ReprEnum)]
 pub enum ReferralLevel {
     /// DFS referral version 1
     V1 = 1,
@@ -183,8 +185,9 @@ pub struct ReferralEntryValueV1 {
 
 /// Type of server hosting the target
 #[binrw::binrw]
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, smb_dtyp::ReprEnum)]
 #[brw(repr(u16))]
+#[repr(u16)]
 pub enum DfsServerType {
     /// Non-root targets returned.
     NonRoot = 0x0,
@@ -4,6 +4,10 @@ use smb_msg_derive::*;
 
 /// SMB2 Echo request/response.
 ///
+/// Echo is commonly used as a keepalive, or to probe the latency of a connection.
+/// The message carries no data, so any timing must be tracked by the caller,
+/// e.g. by recording the send time against the message ID.
+///
 /// MS-SMB2 2.2.28; 2.2.29
 #[smb_request_response(size = 4)]
 #[derive(Default)]
@@ -11,6 +15,11 @@ pub struct EchoMessage {
     reserved: u16,
 }
 
+impl EchoMessage {
+    /// The size of an encoded echo message, following the SMB2 header.
+    pub const PAYLOAD_SIZE: usize = 4;
+}
+
 /// Echo Request is the same as Echo Response (see: [`EchoMessage`])
 pub use EchoMessage as EchoRequest;
 /// Echo Response is the same as Echo Request (see: [`EchoMessage`])
@@ -25,4 +34,22 @@ mod tests {
     test_binrw! {
         struct EchoMessage {} => "04000000"
     }
+
+    #[test]
+    fn test_echo_payload_size() {
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        EchoMessage::default().write_le(&mut cursor).unwrap();
+        assert_eq!(cursor.into_inner().len(), EchoMessage::PAYLOAD_SIZE);
+    }
+
+    #[test]
+    fn test_echo_unexpected_structure_size() {
+        let err =
+            EchoMessage::read_le(&mut std::io::Cursor::new([0x05, 0x00, 0x00, 0x00])).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("unexpected structure size for EchoMessage: 5"),
+            "{err}"
+        );
+    }
 }
@@ -1,12 +1,16 @@
 //! Error response message
 
 use binrw::prelude::*;
+use std::io::{Cursor, SeekFrom};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
-#[cfg(feature = "client")]
 use binrw::io::TakeSeekExt;
 use smb_dtyp::binrw_util::prelude::*;
+use smb_fscc::ReparseTag;
 use smb_msg_derive::*;
 
+use crate::SymbolicLinkReparseData;
+
 /// The SMB2 ERROR Response packet is sent by the server to respond to a request
 /// that has failed or encountered an error.
 ///
@@ -46,6 +50,7 @@ pub struct ErrorResponseContext {
     #[brw(align_before = 8)]
     /// The length, in bytes, of the ErrorContextData field
     #[bw(try_calc = error_data.len().try_into())]
+    #[br(temp)]
     _error_data_length: u32,
     /// An identifier for the error context
     pub error_id: ErrorId,
@@ -90,6 +95,37 @@ impl ErrorResponseContext {
             ))
         }
     }
+
+    /// Interprets the error data as a [`ShareRedirectErrorContext`].
+    /// Returns an error if the context is not a [`ErrorId::ShareRedirect`] context,
+    /// or if the data could not be parsed.
+    pub fn as_share_redirect(&self) -> crate::Result<ShareRedirectErrorContext> {
+        if self.error_id != ErrorId::ShareRedirect {
+            return Err(crate::SmbMsgError::InvalidData(format!(
+                "Expected a share redirect error context, got {:?}",
+                self.error_id
+            )));
+        }
+        Ok(ShareRedirectErrorContext::read_le(&mut Cursor::new(
+            &self.error_data,
+        ))?)
+    }
+
+    /// Interprets the error data as a [`SymbolicLinkErrorResponse`],
+    /// as returned along with a `STATUS_STOP_ON_SYMLINK` status.
+    /// Returns an error if the context is not a [`ErrorId::Default`] context,
+    /// or if the data could not be parsed.
+    pub fn as_symlink(&self) -> crate::Result<SymbolicLinkErrorResponse> {
+        if self.error_id != ErrorId::Default {
+            return Err(crate::SmbMsgError::InvalidData(format!(
+                "Expected a default error context for symbolic link, got {:?}",
+                self.error_id
+            )));
+        }
+        Ok(SymbolicLinkErrorResponse::read_le(&mut Cursor::new(
+            &self.error_data,
+        ))?)
+    }
 }
 
 /// An identifier for the error context in SMB2 ERROR Context structures.
@@ -97,6 +133,8 @@ impl ErrorResponseContext {
 /// Reference: MS-SMB2 2.2.2.1
 #[smb_response_binrw]
 #[brw(repr(u32))]
+#[repr(u32)]
+#[derive(smb_dtyp::ReprEnum)]
 pub enum ErrorId {
     /// Unless otherwise specified, all errors defined in the MS-SMB2 protocol use this error ID
     Default = 0,
@@ -104,13 +142,197 @@ pub enum ErrorId {
     ShareRedirect = 0x72645253,
 }
 
+/// Symbolic link error response, returned when opening a path that contains a symbolic link.
+///
+/// Reference: MS-SMB2 2.2.2.2.1
+#[smb_message_binrw]
+pub struct SymbolicLinkErrorResponse {
+    #[bw(calc = (Self::HEADER_SIZE + reparse_data.path_buffer.len()) as u32)]
+    #[br(temp)]
+    _sym_link_length: u32,
+    #[bw(calc = Self::SYMLINK_ERROR_TAG)]
+    #[br(temp, assert(_sym_link_error_tag == Self::SYMLINK_ERROR_TAG))]
+    _sym_link_error_tag: u32,
+    #[bw(calc = ReparseTag::Symlink as u32)]
+    #[br(temp, assert(_reparse_tag == ReparseTag::Symlink as u32))]
+    _reparse_tag: u32,
+    #[bw(try_calc = (SymbolicLinkReparseData::HEADER_SIZE + reparse_data.path_buffer.len()).try_into())]
+    #[br(temp)]
+    _reparse_data_length: u16,
+    /// The length, in bytes, of the unparsed portion of the path, following the symbolic link.
+    pub unparsed_path_length: u16,
+    /// The symbolic link data, including the substitute and print names.
+    #[br(map_stream = |s| s.take_seek(_reparse_data_length as u64))]
+    pub reparse_data: SymbolicLinkReparseData,
+}
+
+impl SymbolicLinkErrorResponse {
+    const HEADER_SIZE: usize = 24;
+    const SYMLINK_ERROR_TAG: u32 = 0x4C4D5953;
+}
+
+/// Share redirect error context, returned when the client must reconnect to another server
+/// that hosts the share, for example in SMB scale-out scenarios.
+///
+/// Reference: MS-SMB2 2.2.2.2.2
+#[smb_message_binrw]
+pub struct ShareRedirectErrorContext {
+    #[bw(calc = 48)]
+    #[br(temp)]
+    _structure_size: u32,
+    #[bw(calc = 3)]
+    #[br(temp, assert(_notification_type == 3))]
+    _notification_type: u32,
+    #[bw(calc = PosMarker::default())]
+    #[br(temp)]
+    _resource_name_offset: PosMarker<u32>,
+    #[bw(try_calc = resource_name.size().try_into())]
+    #[br(temp)]
+    _resource_name_length: u32,
+    /// Flags, must be 0.
+    reserved: u16,
+    #[bw(calc = 0)]
+    #[br(temp, assert(_target_type == 0))]
+    _target_type: u16,
+    #[bw(try_calc = ip_addr_move_list.len().try_into())]
+    #[br(temp)]
+    _ip_addr_count: u32,
+    /// The IP addresses of the destination servers the client should connect to.
+    #[br(count = _ip_addr_count)]
+    pub ip_addr_move_list: Vec<MoveDstIpAddr>,
+    /// The name of the resource that is being redirected.
+    #[br(seek_before = SeekFrom::Start(_resource_name_offset.value as u64))]
+    #[br(args {size: SizedStringSize::bytes(_resource_name_length)})]
+    #[bw(write_with = PosMarker::write_aoff, args(&_resource_name_offset))]
+    pub resource_name: SizedWideString,
+}
+
+/// A destination IP address in a [`ShareRedirectErrorContext`].
+///
+/// Reference: MS-SMB2 2.2.2.2.2.1
+#[smb_message_binrw]
+pub enum MoveDstIpAddr {
+    /// An IPv4 address.
+    #[brw(magic(b"\x01\x00\x00\x00\x00\x00\x00\x00"))]
+    V4(
+        #[br(map = |x: [u8; 4]| Ipv4Addr::from(x))]
+        #[bw(map = |x: &Ipv4Addr| x.octets())]
+        #[brw(pad_after = 12)]
+        Ipv4Addr,
+    ),
+    /// An IPv6 address.
+    #[brw(magic(b"\x02\x00\x00\x00\x00\x00\x00\x00"))]
+    V6(
+        #[br(map = |x: [u8; 16]| Ipv6Addr::from(x))]
+        #[bw(map = |x: &Ipv6Addr| x.octets())]
+        Ipv6Addr,
+    ),
+}
+
+impl MoveDstIpAddr {
+    /// Returns the destination IP address.
+    pub fn ip(&self) -> IpAddr {
+        match self {
+            MoveDstIpAddr::V4(addr) => IpAddr::V4(*addr),
+            MoveDstIpAddr::V6(addr) => IpAddr::V6(*addr),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::*;
+    use std::net::IpAddr;
 
     test_response! {
         error_simple, Command::Cancel => Error { error_data: vec![], } => "0900000000000000"
     }
 
     // TODO(TEST): Add a test with added context items.
+
+    const SHARE_REDIRECT_DATA: &str = "3000000003000000480000000c000000000000000200000001000000000000000a000001 000000000000000000000000020000000000000020010db8 000000000000000000000001730068006100720065003200 ";
+
+    const SYMLINK_DATA: &str = "3000000053594d4c0c0000a0240002000000100010000800000000005c003f003f005c0043003a005c00740043003a005c007400";
+
+    fn symlink_for_test() -> SymbolicLinkErrorResponse {
+        let path_buffer = "\\??\\C:\\tC:\\t"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        SymbolicLinkErrorResponse {
+            unparsed_path_length: 2,
+            reparse_data: SymbolicLinkReparseData {
+                substitute_name_offset: 0,
+                substitute_name_length: 16,
+                print_name_offset: 16,
+                print_name_length: 8,
+                flags: 0,
+                path_buffer,
+            },
+        }
+    }
+
+    smb_tests::test_binrw! {
+        SymbolicLinkErrorResponse: symlink_for_test() => SYMLINK_DATA
+    }
+
+    #[test]
+    fn test_as_symlink() {
+        let context = ErrorResponseContext {
+            error_id: ErrorId::Default,
+            error_data: smb_tests::hex_to_u8_array! {SYMLINK_DATA},
+        };
+        let symlink = context.as_symlink().unwrap();
+        assert_eq!(symlink.reparse_data.substitute_name(), r"\??\C:\t");
+        assert_eq!(symlink.reparse_data.print_name(), r"C:\t");
+        assert_eq!(symlink.unparsed_path_length, 2);
+        assert!(!symlink.reparse_data.is_relative());
+
+        let context = ErrorResponseContext {
+            error_id: ErrorId::ShareRedirect,
+            error_data: context.error_data,
+        };
+        assert!(context.as_symlink().is_err());
+    }
+
+    fn share_redirect_for_test() -> ShareRedirectErrorContext {
+        ShareRedirectErrorContext {
+            ip_addr_move_list: vec![
+                MoveDstIpAddr::V4("10.0.0.1".parse().unwrap()),
+                MoveDstIpAddr::V6("2001:db8::1".parse().unwrap()),
+            ],
+            resource_name: "share2".into(),
+        }
+    }
+
+    smb_tests::test_binrw! {
+        ShareRedirectErrorContext: share_redirect_for_test() => SHARE_REDIRECT_DATA
+    }
+
+    #[test]
+    fn test_as_share_redirect() {
+        let context = ErrorResponseContext {
+            error_id: ErrorId::ShareRedirect,
+            error_data: smb_tests::hex_to_u8_array! {SHARE_REDIRECT_DATA},
+        };
+        let redirect = context.as_share_redirect().unwrap();
+        assert_eq!(redirect, share_redirect_for_test());
+        assert_eq!(
+            redirect
+                .ip_addr_move_list
+                .iter()
+                .map(MoveDstIpAddr::ip)
+                .collect::<Vec<_>>(),
+            vec![
+                "10.0.0.1".parse::<IpAddr>().unwrap(),
+                "2001:db8::1".parse().unwrap()
+            ]
+        );
+
+        let context = ErrorResponseContext {
+            error_id: ErrorId::Default,
+            error_data: context.error_data,
+        };
+        assert!(context.as_share_redirect().is_err());
+    }
 }
@@ -83,6 +83,12 @@ pub struct ReadRequest {
 ///
 /// Sent by the server with the data read from the file.
 ///
+/// When [`ReadFlags::read_compressed`] is set in the request, the server may compress the
+/// whole response message, sending it as a [`CompressedMessage`][crate::CompressedMessage].
+/// Decompression happens before this structure is parsed, so [`buffer`][Self::buffer]
+/// always contains the plain data, and whether compression was applied is a property
+/// of the transport message rather than of this structure.
+///
 /// Reference: MS-SMB2 2.2.20
 #[smb_response(size = 17)]
 pub struct ReadResponse {
@@ -100,7 +106,8 @@ pub struct ReadResponse {
     #[br(temp)]
     _data_remaining: u32,
 
-    reserved: u32,
+    /// Response flags (SMB 3.1.1 only, reserved otherwise).
+    pub flags: ReadResponseFlags,
 
     /// Data read from the file.
     #[br(seek_before = SeekFrom::Start(_data_offset.value as u64))]
@@ -114,6 +121,17 @@ impl ReadResponse {
     pub const STRUCT_SIZE: usize = 17;
 }
 
+/// Flags for SMB2 READ Response.
+///
+/// Reference: MS-SMB2 2.2.20
+#[smb_dtyp::mbitfield]
+pub struct ReadResponseFlags {
+    /// The data is transformed by RDMA transforms, and the buffer contains an RDMA transform header.
+    pub rdma_transform: bool,
+    #[skip]
+    __: B31,
+}
+
 /// Flags for read operations.
 ///
 /// Reference: MS-SMB2 2.2.19
@@ -122,6 +140,9 @@ pub struct ReadFlags {
     /// Bypass cache and read directly from disk.
     pub read_unbuffered: bool,
     /// Request compressed data.
+    ///
+    /// The server may then compress the response message as a whole;
+    /// see [`ReadResponse`] for details.
     pub read_compressed: bool,
     #[skip]
     __: B6,
@@ -132,6 +153,8 @@ pub struct ReadFlags {
 /// Reference: MS-SMB2 2.2.19
 #[smb_request_binrw]
 #[brw(repr(u32))]
+#[repr(u32)]
+#[derive(smb_dtyp::ReprEnum)]
 pub enum CommunicationChannel {
     /// No RDMA channel.
     None = 0,
@@ -196,6 +219,42 @@ impl WriteRequest {
     }
 }
 
+/// SMB2 WRITE Request, with the data to write embedded right after the message.
+///
+/// Unlike [`WriteRequest`], which leaves sending the data to the caller, this serializes
+/// the complete request, including the data, in a single write. The data offset is
+/// relative to the start of the stream, so write the SMB2 header into the same stream first.
+///
+/// Reference: MS-SMB2 2.2.21
+#[smb_request_binrw]
+pub struct WriteRequestWithData {
+    /// The write request. Its `length` must equal the length of `data`.
+    pub request: WriteRequest,
+    /// The data to write.
+    #[br(count = request.length)]
+    #[bw(assert(data.len() == request.length as usize, "length must match data length"))]
+    pub data: Vec<u8>,
+}
+
+impl WriteRequestWithData {
+    /// Creates a request writing `data` at `offset` of `file_id`.
+    ///
+    /// Fails if `data` is too large to be described by a single write request.
+    pub fn new(
+        offset: u64,
+        file_id: FileId,
+        flags: WriteFlags,
+        data: Vec<u8>,
+    ) -> crate::Result<Self> {
+        let length = u32::try_from(data.len())
+            .map_err(|_| crate::SmbMsgError::InvalidData("Write data is too large".into()))?;
+        Ok(Self {
+            request: WriteRequest::new(offset, file_id, flags, length),
+            data,
+        })
+    }
+}
+
 /// SMB2 WRITE Response.
 ///
 /// Sent by the server to confirm that data has been written.
@@ -265,10 +324,18 @@ mod tests {
 
     test_response! {
         Read {
+            flags: ReadResponseFlags::new(),
             buffer: b"bbbbbb".to_vec(),
         } => "11005000060000000000000000000000626262626262"
     }
 
+    test_response! {
+        rdma_transform: Read {
+            flags: ReadResponseFlags::new().with_rdma_transform(true),
+            buffer: b"bbbbbb".to_vec(),
+        } => "11005000060000000000000001000000626262626262"
+    }
+
     test_request! {
         Write {
             offset: 0x1234abcd,
@@ -283,6 +350,35 @@ mod tests {
         } => "3100700016000000cdab341200000000140400000c000000510010000c00000000000000000000000000000000000000"
     }
 
+    test_binrw_request! {
+        struct WriteRequestWithData {
+            request: WriteRequest::new(
+                0x1234abcd,
+                [
+                    0x14, 0x04, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x51, 0x00, 0x10, 0x00, 0x0c,
+                    0x00, 0x00, 0x00,
+                ]
+                .into(),
+                WriteFlags::new(),
+                9,
+            ),
+            data: b"MeFriend!".to_vec(),
+        } => "3100300009000000cdab341200000000140400000c000000510010000c000000000000000000000000000000000000004d65467269656e6421"
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_write_request_with_data_length_mismatch() {
+        let mut request =
+            WriteRequestWithData::new(0, FileId::EMPTY, WriteFlags::new(), b"data".to_vec())
+                .unwrap();
+        assert_eq!(request.request.length, 4);
+
+        request.data.push(b'!');
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        assert!(request.write_le(&mut cursor).is_err());
+    }
+
     test_binrw_response! {
         struct WriteResponse { count: 0xbeefbaaf, } => "11000000afbaefbe0000000000000000"
     }
@@ -1,15 +1,13 @@
 //! Plain Message Header and related types.
 
-use std::io::Cursor;
-
 use binrw::prelude::*;
 use modular_bitfield::prelude::*;
-use smb_msg_derive::{smb_message_binrw, smb_request_response};
+use smb_msg_derive::smb_request_response;
 
 /// SMB2/SMB3 protocol command codes.
 ///
 /// Reference: MS-SMB2 2.2.1.2
-#[derive(BinRead, BinWrite, Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(BinRead, BinWrite, Debug, PartialEq, Eq, Clone, Copy, smb_dtyp::ReprEnum)]
 #[brw(repr(u16))]
 pub enum Command {
     Negotiate = 0,
@@ -71,15 +69,20 @@ macro_rules! make_status {
 ///
 /// For each status code, a U32 constant is also provided for easier access.
 /// for example, [`Status::U32_END_OF_FILE`] is `0xC0000011`, matching [`Status::EndOfFile`].
-#[smb_message_binrw]
-#[derive(Clone, Copy)]
-#[repr(u32)]
-#[brw(repr(u32))]
+///
+/// Status codes that are not defined here are parsed as [`Status::Unknown`],
+/// so reading a status never fails.
+#[binrw::binrw]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[br(map = |x: u32| Self::from(x))]
+#[bw(map = |x: &Self| u32::from(*x))]
 pub enum Status {
     $(
         #[doc = concat!($description, " (", stringify!($value), ")")]
-        $name = $value,
+        $name,
     )+
+    /// A status code that is not defined in this enum.
+    Unknown(u32),
 }
 
 impl std::fmt::Display for Status {
@@ -88,8 +91,9 @@ impl std::fmt::Display for Status {
             $(
                 Status::$name => $description,
             )+
+            Status::Unknown(_) => "Unknown",
         };
-        write!(f, "{} ({:#x})", message_as_string, *self as u32)
+        write!(f, "{} ({:#x})", message_as_string, u32::from(*self))
     }
 }
 
@@ -102,26 +106,48 @@ impl Status {
         )+
     }
 
+    /// Returns the raw NT status code.
+    pub const fn as_u32(&self) -> u32 {
+        match self {
+            $(
+                Status::$name => $value,
+            )+
+            Status::Unknown(value) => *value,
+        }
+    }
+
+    /// Returns true if the status is not one of the status codes defined in this enum.
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, Status::Unknown(_))
+    }
+
     /// A helper function that tries converting u32 to a [`Status`],
     /// and returns a string representation of the status. Otherwise,
     /// it returns the hex representation of the u32 value.
     /// This is useful for displaying NT status codes that are not necessarily
     /// defined in the [`Status`] enum.
     pub fn try_display_as_status(value: u32) -> String {
-        match Self::try_from(value) {
-            Ok(status) => format!("{}", status),
-            Err(_) => format!("{:#06x}", value),
+        match Self::from(value) {
+            Status::Unknown(_) => format!("{:#06x}", value),
+            status => format!("{}", status),
         }
     }
 }
 
-impl TryFrom<u32> for Status {
-    type Error = crate::SmbMsgError;
+impl From<u32> for Status {
+    fn from(value: u32) -> Self {
+        match value {
+            $(
+                $value => Status::$name,
+            )+
+            _ => Status::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u32) -> Result<Self, Self::Error> {
-        Status::read_le(&mut Cursor::new(value.to_le_bytes())).map_err(|_| {
-            Self::Error::MissingErrorCodeDefinition(value)
-        })
+impl From<Status> for u32 {
+    fn from(value: Status) -> Self {
+        value.as_u32()
     }
 }
     };
@@ -129,7 +155,9 @@ impl TryFrom<u32> for Status {
 
 make_status! {
     Success = 0x00000000: "Success",
+    Timeout = 0x00000102: "Timeout",
     Pending = 0x00000103: "Pending",
+    OplockBreakInProgress = 0x00000108: "Oplock Break in Progress",
     NotifyCleanup = 0x0000010B: "Notify Cleanup",
     NotifyEnumDir = 0x0000010C: "Notify Enum Dir",
     InvalidSmb = 0x00010002: "Invalid SMB",
@@ -139,15 +167,20 @@ make_status! {
     SmbUseStandard = 0x00FB0002: "SMB Use Standard",
     BufferOverflow = 0x80000005: "Buffer Overflow",
     NoMoreFiles = 0x80000006: "No More Files",
+    NoMoreEntries = 0x8000001A: "No More Entries",
     StoppedOnSymlink = 0x8000002D: "Stopped on Symlink",
+    Unsuccessful = 0xC0000001: "Unsuccessful",
     NotImplemented = 0xC0000002: "Not Implemented",
     InvalidInfoClass = 0xC0000003: "Invalid Info Class",
     InfoLengthMismatch = 0xC0000004: "Info Length Mismatch",
+    InvalidHandle = 0xC0000008: "Invalid Handle",
     InvalidParameter = 0xC000000D: "Invalid Parameter",
     NoSuchDevice = 0xC000000E: "No Such Device",
+    NoSuchFile = 0xC000000F: "No Such File",
     InvalidDeviceRequest0 = 0xC0000010: "Invalid Device Request",
     EndOfFile = 0xC0000011: "End of File",
     MoreProcessingRequired = 0xC0000016: "More Processing Required",
+    NoMemory = 0xC0000017: "No Memory",
     AccessDenied = 0xC0000022: "Access Denied",
     BufferTooSmall = 0xC0000023: "Buffer Too Small",
     ObjectNameInvalid = 0xC0000033: "Object Name Invalid",
@@ -155,24 +188,65 @@ make_status! {
     ObjectNameCollision = 0xC0000035: "Object Name Collision",
     SharingViolation = 0xC0000043: "Sharing Violation",
     ObjectPathNotFound = 0xC000003A: "Object Path Not Found",
+    ObjectPathSyntaxBad = 0xC000003B: "Object Path Syntax Bad",
     NoEasOnFile = 0xC0000044: "No EAs on File",
+    FileLockConflict = 0xC0000054: "File Lock Conflict",
+    LockNotGranted = 0xC0000055: "Lock Not Granted",
+    DeletePending = 0xC0000056: "Delete Pending",
+    NoSuchUser = 0xC0000064: "No Such User",
+    WrongPassword = 0xC000006A: "Wrong Password",
     LogonFailure = 0xC000006D: "Logon Failure",
+    AccountRestriction = 0xC000006E: "Account Restriction",
+    InvalidLogonHours = 0xC000006F: "Invalid Logon Hours",
+    InvalidWorkstation = 0xC0000070: "Invalid Workstation",
+    PasswordExpired = 0xC0000071: "Password Expired",
+    AccountDisabled = 0xC0000072: "Account Disabled",
     NotMapped = 0xC0000073: "Not Mapped",
+    RangeNotLocked = 0xC000007E: "Range Not Locked",
+    DiskFull = 0xC000007F: "Disk Full",
+    InsufficientResources = 0xC000009A: "Insufficient Resources",
+    MediaWriteProtected = 0xC00000A2: "Media Write Protected",
     BadImpersonationLevel = 0xC00000A5: "Bad Impersonation Level",
+    PipeNotAvailable = 0xC00000AC: "Pipe Not Available",
+    InvalidPipeState = 0xC00000AD: "Invalid Pipe State",
+    PipeBusy = 0xC00000AE: "Pipe Busy",
+    PipeDisconnected = 0xC00000B0: "Pipe Disconnected",
     IoTimeout = 0xC00000B5: "I/O Timeout",
     FileIsADirectory = 0xC00000BA: "File is a Directory",
     NotSupported = 0xC00000BB: "Not Supported",
+    BadNetworkPath = 0xC00000BE: "Bad Network Path",
+    InvalidNetworkResponse = 0xC00000C3: "Invalid Network Response",
+    UnexpectedNetworkError = 0xC00000C4: "Unexpected Network Error",
     NetworkNameDeleted = 0xC00000C9: "Network Name Deleted",
+    NetworkAccessDenied = 0xC00000CA: "Network Access Denied",
     BadNetworkName = 0xC00000CC: "Bad Network Name",
     RequestNotAccepted = 0xC00000D0: "Request Not Accepted",
+    NotSameDevice = 0xC00000D4: "Not Same Device",
+    FileRenamed = 0xC00000D5: "File Renamed",
+    PipeEmpty = 0xC00000D9: "Pipe Empty",
     DirectoryNotEmpty = 0xC0000101: "Directory Not Empty",
+    NotADirectory = 0xC0000103: "Not a Directory",
     Cancelled = 0xC0000120: "Cancelled",
+    CannotDelete = 0xC0000121: "Cannot Delete",
+    FileDeleted = 0xC0000123: "File Deleted",
+    FileClosed = 0xC0000128: "File Closed",
+    PipeBroken = 0xC000014B: "Pipe Broken",
+    InvalidDeviceState = 0xC0000184: "Invalid Device State",
+    AccountExpired = 0xC0000193: "Account Expired",
     UserSessionDeleted = 0xC0000203: "User Session Deleted",
+    ConnectionDisconnected = 0xC000020C: "Connection Disconnected",
+    ConnectionReset = 0xC000020D: "Connection Reset",
+    PasswordMustChange = 0xC0000224: "Password Must Change",
+    NotFound = 0xC0000225: "Not Found",
     UserAccountLockedOut = 0xC0000234: "User Account Locked Out",
     PathNotCovered = 0xC0000257: "Path Not Covered",
+    NotAReparsePoint = 0xC0000275: "Not a Reparse Point",
     NetworkSessionExpired = 0xC000035C: "Network Session Expired",
     SmbTooManyUids = 0xC000205A: "SMB Too Many UIDs",
     DeviceFeatureNotSupported = 0xC0000463: "Device Feature Not Supported",
+    ServerUnavailable = 0xC0000466: "Server Unavailable",
+    FileNotAvailable = 0xC0000467: "File Not Available",
+    InvalidSignature = 0xC000A000: "Invalid Signature",
 }
 
 /// SMB2 Packet Header.
@@ -223,10 +297,9 @@ pub struct Header {
 impl Header {
     pub const STRUCT_SIZE: usize = 64;
 
-    /// Tries to convert the [`Header::status`] field to a [`Status`],
-    /// returning it, if successful.
-    pub fn status(&self) -> crate::Result<Status> {
-        self.status.try_into()
+    /// Converts the [`Header::status`] field to a [`Status`].
+    pub fn status(&self) -> Status {
+        self.status.into()
     }
 
     /// Turns the current header into an async header,
@@ -237,6 +310,133 @@ impl Header {
         self.tree_id = None;
         self.async_id = Some(async_id);
     }
+
+    /// Returns a builder for a synchronous header of the given command.
+    pub fn builder(command: Command) -> HeaderBuilder {
+        HeaderBuilder {
+            header: Header {
+                credit_charge: 0,
+                status: Status::U32_SUCCESS,
+                command,
+                credit_request: 0,
+                flags: HeaderFlags::new(),
+                next_command: 0,
+                message_id: 0,
+                tree_id: Some(0),
+                async_id: None,
+                session_id: 0,
+                signature: 0,
+            },
+        }
+    }
+}
+
+/// A builder for [`Header`]s. Use [`Header::builder`] to create one.
+///
+/// By default, the header is synchronous (tree ID 0), successful,
+/// with no flags set and all other fields zeroed.
+#[derive(Debug)]
+pub struct HeaderBuilder {
+    header: Header,
+}
+
+impl HeaderBuilder {
+    /// Sets the credit charge.
+    pub fn credit_charge(mut self, credit_charge: u16) -> Self {
+        self.header.credit_charge = credit_charge;
+        self
+    }
+
+    /// Sets the credits requested or granted.
+    pub fn credit_request(mut self, credit_request: u16) -> Self {
+        self.header.credit_request = credit_request;
+        self
+    }
+
+    /// Sets the NT status code.
+    pub fn status(mut self, status: Status) -> Self {
+        self.header.status = status.into();
+        self
+    }
+
+    /// Sets the message ID.
+    pub fn message_id(mut self, message_id: u64) -> Self {
+        self.header.message_id = message_id;
+        self
+    }
+
+    /// Sets the offset to the next message in a compound chain.
+    pub fn next_command(mut self, next_command: u32) -> Self {
+        self.header.next_command = next_command;
+        self
+    }
+
+    /// Sets the session ID.
+    pub fn session_id(mut self, session_id: u64) -> Self {
+        self.header.session_id = session_id;
+        self
+    }
+
+    /// Sets the tree ID, making the header synchronous.
+    pub fn tree_id(mut self, tree_id: u32) -> Self {
+        self.header.flags.set_async_command(false);
+        self.header.async_id = None;
+        self.header.tree_id = Some(tree_id);
+        self
+    }
+
+    /// Sets the async ID, making the header asynchronous. See [`Header::to_async`].
+    pub fn async_id(mut self, async_id: u64) -> Self {
+        self.header.to_async(async_id);
+        self
+    }
+
+    /// Sets the message signature.
+    pub fn signature(mut self, signature: u128) -> Self {
+        self.header.signature = signature;
+        self
+    }
+
+    /// Sets [`HeaderFlags::signed`].
+    pub fn signed(mut self, signed: bool) -> Self {
+        self.header.flags.set_signed(signed);
+        self
+    }
+
+    /// Sets [`HeaderFlags::related_operations`].
+    pub fn related(mut self, related: bool) -> Self {
+        self.header.flags.set_related_operations(related);
+        self
+    }
+
+    /// Sets [`HeaderFlags::server_to_redir`].
+    pub fn server_to_redir(mut self, server_to_redir: bool) -> Self {
+        self.header.flags.set_server_to_redir(server_to_redir);
+        self
+    }
+
+    /// Sets [`HeaderFlags::priority_mask`]. Only the low 3 bits are used.
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.header.flags.set_priority_mask(priority & 0b111);
+        self
+    }
+
+    /// Sets [`HeaderFlags::dfs_operation`].
+    pub fn dfs_operation(mut self, dfs_operation: bool) -> Self {
+        self.header.flags.set_dfs_operation(dfs_operation);
+        self
+    }
+
+    /// Sets [`HeaderFlags::replay_operation`].
+    pub fn replay_operation(mut self, replay_operation: bool) -> Self {
+        self.header.flags.set_replay_operation(replay_operation);
+        self
+    }
+
+    /// Builds the [`Header`].
+    pub fn build(self) -> Header {
+        self.header
+    }
 }
 
 /// SMB2 header flags.
@@ -275,7 +475,7 @@ mod tests {
     test_binrw! {
         Header => async: Header {
             credit_charge: 0,
-            status: Status::Pending as u32,
+            status: Status::U32_PENDING,
             command: Command::ChangeNotify,
             credit_request: 1,
             flags: HeaderFlags::new()
@@ -292,4 +492,103 @@ mod tests {
             )),
         } => "fe534d4240000000030100000f000100130000000000000008000000000000000800000000000000d72753080000000063f825deae02952fa3d8c8aaf46e7c99"
     }
+
+    test_binrw! {
+        Header => signed_related: Header::builder(Command::Create)
+            .credit_request(1)
+            .message_id(5)
+            .tree_id(1)
+            .session_id(0x11)
+            .signed(true)
+            .related(true)
+            .build() => "fe534d424000000000000000050001000c0000000000000005000000000000000000000001000000110000000000000000000000000000000000000000000000"
+    }
+
+    #[test]
+    fn test_header_builder_flags() {
+        let header = Header::builder(Command::Create)
+            .signed(true)
+            .related(true)
+            .priority(0xff)
+            .build();
+        assert!(header.flags.signed());
+        assert!(header.flags.related_operations());
+        assert!(!header.flags.async_command());
+        assert_eq!(header.flags.priority_mask(), 0b111);
+        assert_eq!(u32::from_le_bytes(header.flags.into_bytes()), 0x7c);
+
+        let header = Header::builder(Command::ChangeNotify).async_id(8).build();
+        assert!(header.flags.async_command());
+        assert_eq!(header.tree_id, None);
+        assert_eq!(header.async_id, Some(8));
+    }
+
+    #[test]
+    fn test_header_flags_display() {
+        assert_eq!(HeaderFlags::new().to_string(), "");
+        assert_eq!(
+            HeaderFlags::new()
+                .with_server_to_redir(true)
+                .with_signed(true)
+                .with_priority_mask(3)
+                .with_replay_operation(true)
+                .to_string(),
+            "server_to_redir|signed|priority_mask=3|replay_operation"
+        );
+    }
+
+    #[test]
+    fn test_header_flags_reflection() {
+        assert_eq!(
+            HeaderFlags::FIELD_NAMES,
+            &[
+                "server_to_redir",
+                "async_command",
+                "related_operations",
+                "signed",
+                "priority_mask",
+                "dfs_operation",
+                "replay_operation",
+            ]
+        );
+        assert!(HeaderFlags::new().set_flags().is_empty());
+        assert_eq!(
+            HeaderFlags::new()
+                .with_signed(true)
+                .with_related_operations(true)
+                .with_priority_mask(1)
+                .set_flags(),
+            vec!["related_operations", "signed"]
+        );
+    }
+
+    test_binrw! {
+        Status => success: Status::Success => "00000000"
+    }
+
+    test_binrw! {
+        Status => stopped_on_symlink: Status::StoppedOnSymlink => "2d000080"
+    }
+
+    test_binrw! {
+        Status => access_denied: Status::AccessDenied => "220000c0"
+    }
+
+    test_binrw! {
+        Status => unknown: Status::Unknown(0xC0DE0001) => "0100dec0"
+    }
+
+    #[test]
+    fn test_status_u32_conversions() {
+        assert_eq!(Status::from(Status::U32_NO_MORE_FILES), Status::NoMoreFiles);
+        assert_eq!(
+            Status::from(Status::U32_MORE_PROCESSING_REQUIRED),
+            Status::MoreProcessingRequired
+        );
+        assert_eq!(Status::from(0xC0DE0001), Status::Unknown(0xC0DE0001));
+        assert!(Status::from(0xC0DE0001).is_unknown());
+        assert_eq!(u32::from(Status::Unknown(0xC0DE0001)), 0xC0DE0001);
+        assert_eq!(Status::AccessDenied.as_u32(), 0xC0000022);
+        assert_eq!(Status::try_display_as_status(0xC0DE0001), "0xc0de0001");
+    }
 }
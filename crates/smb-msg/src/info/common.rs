@@ -4,8 +4,9 @@ use binrw::prelude::*;
 use modular_bitfield::prelude::*;
 
 #[smb_message_binrw]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, smb_dtyp::ReprEnum)]
 #[brw(repr(u8))]
+#[repr(u8)]
 pub enum InfoType {
     File = 0x1,
     FileSystem = 0x2,
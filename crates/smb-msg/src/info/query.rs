@@ -246,6 +246,16 @@ impl QueryInfoResponseData {
     }
 }
 
+/// Parses a raw file information buffer, such as a query info response output buffer,
+/// into the [`QueryFileInfo`] variant matching `class`.
+///
+/// This is a shortcut for generic query code, which would otherwise have to go through
+/// [`QueryInfoResponseData::parse`] and [`RawQueryInfoData::parse`].
+pub fn parse_file_info(class: QueryFileInfoClass, buffer: &[u8]) -> crate::Result<QueryFileInfo> {
+    let mut cursor = Cursor::new(buffer);
+    Ok(QueryFileInfo::read_le_args(&mut cursor, (class,))?)
+}
+
 impl From<Vec<u8>> for QueryInfoResponseData {
     fn from(data: Vec<u8>) -> Self {
         QueryInfoResponseData { data }
@@ -335,6 +345,56 @@ mod tests {
         } => "09004800280000005b6c44ce6a58db01048fa10d516bdb01048fa10d516bdb01048fa10d516bdb012000000000000000"
     }
 
+    #[test]
+    fn test_parse_file_info_standard() {
+        let buffer =
+            smb_tests::hex_to_u8_array! {"00100000000000000d000000000000000000000001000000"};
+        assert_eq!(
+            parse_file_info(QueryFileInfoClass::StandardInformation, &buffer).unwrap(),
+            QueryFileInfo::StandardInformation(FileStandardInformation {
+                allocation_size: 4096,
+                end_of_file: 13,
+                number_of_links: 0,
+                delete_pending: true.into(),
+                directory: false.into(),
+            })
+        );
+        assert!(parse_file_info(QueryFileInfoClass::StandardInformation, &buffer[..8]).is_err());
+    }
+
+    #[test]
+    fn test_parse_file_info_all() {
+        let buffer = smb_tests::hex_to_u8_array! {
+            "681621b5513fdc01681621b5513fdc01681621b5513fdc01681621b5513fdc012000000000000000
+            00100000000000000d000000000000000000000001000000
+            163b030000000000
+            d0000000
+            ff011f00
+            0004000000000000
+            20000000
+            00000000
+            1a000000460069006c0065005f004e0061006d0065002e00740078007400"
+        };
+        let info = parse_file_info(QueryFileInfoClass::AllInformation, &buffer).unwrap();
+        let QueryFileInfo::AllInformation(all) = info else {
+            panic!("Expected FileAllInformation, got {info:?}");
+        };
+        assert_eq!(
+            all.basic.creation_time,
+            datetime!(2025-10-17 10:35:07.801764000).into()
+        );
+        assert_eq!(all.standard.end_of_file, 13);
+        assert_eq!(all.internal.index_number, 0x33b16);
+        assert_eq!(all.ea.ea_size, 208);
+        assert_eq!(
+            all.access.access_flags,
+            FileAccessMask::from_bytes(0x001f01ffu32.to_le_bytes())
+        );
+        assert_eq!(all.position.current_byte_offset, 1024);
+        assert_eq!(all.alignment, FileAlignmentInformation::Byte);
+        assert_eq!(all.name, FileNameInformation::from("File_Name.txt"));
+    }
+
     #[test]
     pub fn test_query_info_resp_parse_file() {
         let raw_data: QueryInfoResponseData = [
@@ -71,6 +71,18 @@ pub enum SetInfoClass {
     Quota(NullByte),
 }
 
+impl SetInfoClass {
+    /// Get the name of the info type of this class.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SetInfoClass::File(_) => "File",
+            SetInfoClass::FileSystem(_) => "FileSystem",
+            SetInfoClass::Security(_) => "Security",
+            SetInfoClass::Quota(_) => "Quota",
+        }
+    }
+}
+
 impl From<SetFileInfoClass> for SetInfoClass {
     fn from(val: SetFileInfoClass) -> Self {
         SetInfoClass::File(val)
@@ -84,21 +96,63 @@ impl From<SetFileSystemInfoClass> for SetInfoClass {
 }
 
 impl SetInfoData {
+    /// Wraps a file information value, returning it together with its matching info class.
+    ///
+    /// The returned pair can be passed straight to [`SetInfoData::to_req`],
+    /// so the class and data cannot get out of sync.
+    pub fn file<T: SetFileInfoValue>(value: T) -> (SetInfoClass, SetInfoData) {
+        (
+            T::CLASS_ID.into(),
+            RawSetInfoData::<SetFileInfo>::from(value.into()).into(),
+        )
+    }
+
+    /// Builds a [`FileRenameInformation`] payload.
+    ///
+    /// `root_directory` must be 0 for SMB2 (MS-FSCC 2.4.42.2).
+    pub fn rename(
+        file_name: impl Into<SizedWideString>,
+        replace_if_exists: bool,
+        root_directory: u64,
+    ) -> (SetInfoClass, SetInfoData) {
+        Self::file(FileRenameInformation {
+            replace_if_exists: replace_if_exists.into(),
+            root_directory,
+            file_name: file_name.into(),
+        })
+    }
+
+    /// Builds a [`FileEndOfFileInformation`] payload, truncating or extending the file.
+    pub fn end_of_file(end_of_file: u64) -> (SetInfoClass, SetInfoData) {
+        Self::file(FileEndOfFileInformation { end_of_file })
+    }
+
+    /// Builds a [`FileDispositionInformation`] payload, marking (or unmarking) the file for deletion.
+    pub fn disposition(delete_pending: bool) -> (SetInfoClass, SetInfoData) {
+        Self::file(FileDispositionInformation {
+            delete_pending: delete_pending.into(),
+        })
+    }
+
+    /// Builds a [`FileAllocationInformation`] payload.
+    pub fn allocation(allocation_size: u64) -> (SetInfoClass, SetInfoData) {
+        Self::file(FileAllocationInformation { allocation_size })
+    }
+
     /// Creates a SetInfoRequest from this data with the specified parameters.
     ///
     /// Validates that the info class and data combination are compatible before
     /// creating the request structure.
     ///
-    /// # Panics
-    ///
-    /// Panics if the info class and data type combination is invalid
+    /// Returns [`SmbMsgError::InfoClassMismatch`][crate::SmbMsgError::InfoClassMismatch]
+    /// if the info class and data type combination is invalid
     /// (e.g., File class with FileSystem data).
     pub fn to_req(
         self,
         info_class: SetInfoClass,
         file_id: FileId,
         additional_info: AdditionalInfo,
-    ) -> SetInfoRequest {
+    ) -> crate::Result<SetInfoRequest> {
         // Validate the info class and data combination
         // to ensure they are compatible.
         match (&info_class, &self) {
@@ -106,14 +160,41 @@ impl SetInfoData {
             (SetInfoClass::FileSystem(_), SetInfoData::FileSystem(_)) => {}
             (SetInfoClass::Security(_), SetInfoData::Security(_)) => {}
             (SetInfoClass::Quota(_), SetInfoData::Quota(_)) => {}
-            _ => panic!("Invalid info class and data combination"),
+            _ => {
+                return Err(crate::SmbMsgError::InfoClassMismatch {
+                    class: info_class.name(),
+                    data: self.name(),
+                });
+            }
         }
 
-        SetInfoRequest {
+        Ok(SetInfoRequest {
             info_class,
             additional_information: additional_info,
             file_id,
             data: self,
+        })
+    }
+}
+
+impl SetInfoRequest {
+    /// Creates a request renaming the file `file_id` to `new_name`,
+    /// using [`FileRenameExInformation`] with the given `flags`.
+    pub fn rename_ex(
+        file_id: FileId,
+        new_name: impl Into<SizedWideString>,
+        flags: RenameExFlags,
+    ) -> SetInfoRequest {
+        let (info_class, data) = SetInfoData::file(FileRenameExInformation {
+            flags,
+            root_directory: 0,
+            file_name: new_name.into(),
+        });
+        SetInfoRequest {
+            info_class,
+            additional_information: AdditionalInfo::new(),
+            file_id,
+            data,
         }
     }
 }
@@ -147,7 +228,124 @@ mod tests {
         } => "2100010a3a0000006000000000000000420000000e000000050010000e0000000000000000000000000000000000000026000000680065006c006c006f005c006d0079004e0065007700460069006c0065002e00740078007400"
     }
 
+    test_request! {
+        rename_ex: SetInfo {
+            info_class: SetInfoClass::File(SetFileInfoClass::RenameExInformation),
+            data: SetInfoData::from(RawSetInfoData::from(SetFileInfo::RenameExInformation(FileRenameExInformation {
+                flags: RenameExFlags::new().with_posix_semantics(true),
+                root_directory: 0,
+                file_name: "hello\\myNewFile.txt".into(),
+            }))),
+            file_id: make_guid!("00000042-000e-0000-0500-10000e000000").into(),
+            additional_information: AdditionalInfo::new(),
+        } => "210001413a0000006000000000000000420000000e000000050010000e0000000200000000000000000000000000000026000000680065006c006c006f005c006d0079004e0065007700460069006c0065002e00740078007400"
+    }
+
+    #[test]
+    fn test_set_info_rename_ex() {
+        let request = SetInfoRequest::rename_ex(
+            make_guid!("00000042-000e-0000-0500-10000e000000").into(),
+            "hello\\myNewFile.txt",
+            RenameExFlags::new().with_posix_semantics(true),
+        );
+        assert_eq!(
+            request.info_class,
+            SetInfoClass::File(SetFileInfoClass::RenameExInformation)
+        );
+        let info: FileRenameExInformation = request
+            .data
+            .as_file()
+            .unwrap()
+            .parse(SetFileInfoClass::RenameExInformation)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert!(info.flags.posix_semantics());
+        assert!(!info.flags.replace_if_exists());
+        assert_eq!(info.file_name.to_string(), "hello\\myNewFile.txt");
+    }
+
+    #[test]
+    fn test_set_info_data_builders() {
+        let (class, data) = SetInfoData::rename("hello\\myNewFile.txt", false, 0);
+        assert_eq!(
+            class,
+            SetInfoClass::File(SetFileInfoClass::RenameInformation)
+        );
+        assert_eq!(
+            data,
+            SetInfoData::from(RawSetInfoData::from(SetFileInfo::RenameInformation(
+                FileRenameInformation {
+                    replace_if_exists: false.into(),
+                    root_directory: 0,
+                    file_name: "hello\\myNewFile.txt".into(),
+                }
+            )))
+        );
+
+        let (class, data) = SetInfoData::end_of_file(0x1000);
+        assert_eq!(
+            class,
+            SetInfoClass::File(SetFileInfoClass::EndOfFileInformation)
+        );
+        assert_eq!(
+            data,
+            SetInfoData::from(RawSetInfoData::from(SetFileInfo::EndOfFileInformation(
+                FileEndOfFileInformation {
+                    end_of_file: 0x1000
+                }
+            )))
+        );
+
+        let (class, _) = SetInfoData::disposition(true);
+        assert_eq!(
+            class,
+            SetInfoClass::File(SetFileInfoClass::DispositionInformation)
+        );
+        let (class, _) = SetInfoData::allocation(0);
+        assert_eq!(
+            class,
+            SetInfoClass::File(SetFileInfoClass::AllocationInformation)
+        );
+    }
+
+    #[test]
+    fn test_set_info_to_req_mismatch() {
+        let (_, data) = SetInfoData::end_of_file(0);
+        let err = data
+            .to_req(
+                SetInfoClass::Quota(Default::default()),
+                FileId::EMPTY,
+                AdditionalInfo::new(),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SmbMsgError::InfoClassMismatch {
+                class: "Quota",
+                data: "File"
+            }
+        ));
+
+        let (class, data) = SetInfoData::end_of_file(0);
+        assert!(
+            data.to_req(class, FileId::EMPTY, AdditionalInfo::new())
+                .is_ok()
+        );
+    }
+
     test_binrw_response! {
         struct SetInfoResponse {} => "0200"
     }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_set_info_response_unexpected_structure_size() {
+        let err = SetInfoResponse::read_le(&mut std::io::Cursor::new([0x03, 0x00])).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("unexpected structure size for SetInfoResponse: 3"),
+            "{err}"
+        );
+    }
 }
@@ -6,7 +6,7 @@ use modular_bitfield::prelude::*;
 use smb_dtyp::binrw_util::prelude::*;
 use smb_msg_derive::{smb_message_binrw, smb_request_binrw, smb_response_binrw};
 
-use crate::{Dialect, NegotiateSecurityMode};
+use crate::{Dialect, FileId, NegotiateResponse, NegotiateSecurityMode};
 
 use crate::dfsc::{ReqGetDfsReferral, ReqGetDfsReferralEx, RespGetDfsReferral};
 use smb_dtyp::*;
@@ -17,7 +17,7 @@ use crate::IoctlBuffer;
 use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
 use std::ops::{Deref, DerefMut};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, smb_dtyp::ReprEnum)]
 #[repr(u32)]
 pub enum FsctlCodes {
     DfsGetReferrals = 0x00060194,
@@ -33,10 +33,13 @@ pub enum FsctlCodes {
     LmrRequestResiliency = 0x001401D4,
     QueryNetworkInterfaceInfo = 0x001401FC,
     SetReparsePoint = 0x000900A4,
+    GetReparsePoint = 0x000900A8,
     DfsGetReferralsEx = 0x000601B0,
     FileLevelTrim = 0x00098208,
     ValidateNegotiateInfo = 0x00140204,
     QueryAllocatedRanges = 0x000940CF,
+    SetZeroData = 0x000980C8,
+    DuplicateExtentsToFile = 0x00098344,
 }
 
 /// Request packet for initiating a server-side copy of data.
@@ -61,6 +64,45 @@ pub struct SrvCopychunkCopy {
 impl SrvCopychunkCopy {
     pub const SRV_KEY_LENGTH: usize = 24;
     pub const SIZE: usize = Self::SRV_KEY_LENGTH + 4 + 4;
+
+    /// Creates a copychunk request that copies data within a single file.
+    ///
+    /// The resume key should be obtained by sending FSCTL_SRV_REQUEST_RESUME_KEY
+    /// on the same handle that the copychunk request is then sent on,
+    /// so the file acts as both the source and the destination of the copy.
+    ///
+    /// Since the source and destination are the same file, each chunk is validated
+    /// to be non-empty, and its source and target ranges must not overlap.
+    pub fn same_file(
+        resume_key: [u8; Self::SRV_KEY_LENGTH],
+        chunks: Vec<SrvCopychunkItem>,
+    ) -> crate::Result<Self> {
+        for (i, chunk) in chunks.iter().enumerate() {
+            if chunk.length == 0 {
+                return Err(crate::SmbMsgError::InvalidData(format!(
+                    "Copychunk #{i} has zero length"
+                )));
+            }
+            let length = chunk.length as u64;
+            let (Some(source_end), Some(target_end)) = (
+                chunk.source_offset.checked_add(length),
+                chunk.target_offset.checked_add(length),
+            ) else {
+                return Err(crate::SmbMsgError::InvalidData(format!(
+                    "Copychunk #{i} range overflows"
+                )));
+            };
+            if chunk.source_offset < target_end && chunk.target_offset < source_end {
+                return Err(crate::SmbMsgError::InvalidData(format!(
+                    "Copychunk #{i} source and target ranges overlap within the same file"
+                )));
+            }
+        }
+        Ok(Self {
+            source_key: resume_key,
+            chunks,
+        })
+    }
 }
 
 /// Individual data range descriptor for server-side copy operations.
@@ -124,6 +166,8 @@ impl IoctlRequestContent for SrvReadHashReq {
 /// Reference: MS-SMB2 2.2.31.2
 #[smb_request_binrw]
 #[brw(repr(u32))]
+#[repr(u32)]
+#[derive(smb_dtyp::ReprEnum)]
 pub enum SrvHashRetrievalType {
     /// The offset field in the SRV_READ_HASH request is relative to the beginning
     /// of the Content Information File.
@@ -401,6 +445,45 @@ pub struct NetworkInterfaceCapability {
     __: B30,
 }
 
+/// An alternate channel to open, over one of the server's network interfaces.
+///
+/// See [`plan_channels`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelPlan {
+    /// The index of the server network interface.
+    pub if_index: u32,
+    /// The address to connect the channel to.
+    pub address: SocketAddr,
+    /// The speed of the network interface in bits per second.
+    pub link_speed: u64,
+    /// Whether the interface is also RDMA capable.
+    pub rdma: bool,
+}
+
+/// Plans the alternate channels to open for a multi-channel session,
+/// given the negotiate response and the result of FSCTL_QUERY_NETWORK_INTERFACE_INFO.
+///
+/// Returns an empty plan if the server does not support multi-channel
+/// (see [`NegotiateResponse::supports_multichannel`]). Otherwise, returns a channel
+/// for each RSS-capable interface, fastest first.
+pub fn plan_channels(neg: &NegotiateResponse, ifaces: &[NetworkInterfaceInfo]) -> Vec<ChannelPlan> {
+    if !neg.supports_multichannel() {
+        return vec![];
+    }
+    let mut plan: Vec<_> = ifaces
+        .iter()
+        .filter(|iface| iface.capability.rss())
+        .map(|iface| ChannelPlan {
+            if_index: iface.if_index,
+            address: iface.sockaddr.socket_addr(),
+            link_speed: iface.link_speed,
+            rdma: iface.capability.rdma(),
+        })
+        .collect();
+    plan.sort_by_key(|channel| std::cmp::Reverse(channel.link_speed));
+    plan
+}
+
 #[smb_response_binrw]
 pub enum SocketAddrStorage {
     V4(SocketAddrStorageV4),
@@ -585,6 +668,110 @@ impl IoctlRequestContent for SetReparsePointRequest {
     }
 }
 
+/// Reparse point data, returned for FSCTL_GET_REPARSE_POINT.
+///
+/// This is the REPARSE_DATA_BUFFER (or REPARSE_GUID_DATA_BUFFER, for non-Microsoft tags)
+/// structure, as specified in MS-FSCC 2.1.2.
+#[smb_response_binrw]
+pub struct GetReparsePointResponse {
+    /// The reparse point tag that uniquely identifies the owner of the reparse point.
+    #[bw(assert((reparse_tag & 0x80000000 == 0) == reparse_guid.is_some()))]
+    pub reparse_tag: u32,
+    #[bw(try_calc = reparse_data.len().try_into())]
+    #[br(temp)]
+    reparse_data_length: u16,
+    reserved: u16,
+    /// Applicable only for reparse points that have a GUID (non-Microsoft tags).
+    #[br(if(reparse_tag & 0x80000000 == 0))]
+    pub reparse_guid: Option<Guid>,
+    /// Reparse-specific data for the reparse point.
+    #[br(count = reparse_data_length)]
+    pub reparse_data: Vec<u8>,
+}
+
+impl_fsctl_response!(GetReparsePoint, GetReparsePointResponse);
+
+impl GetReparsePointResponse {
+    /// Returns the reparse point tag.
+    pub fn reparse_tag(&self) -> u32 {
+        self.reparse_tag
+    }
+
+    /// Decodes the reparse data as a symbolic link.
+    ///
+    /// Fails if the reparse tag is not [`ReparseTag::Symlink`], or if the data is malformed.
+    pub fn as_symlink(&self) -> crate::Result<SymbolicLinkReparseData> {
+        if self.reparse_tag != ReparseTag::Symlink as u32 {
+            return Err(crate::SmbMsgError::InvalidData(format!(
+                "Reparse tag {:#x} is not a symbolic link",
+                self.reparse_tag
+            )));
+        }
+        Ok(SymbolicLinkReparseData::read_le(
+            &mut std::io::Cursor::new(&self.reparse_data),
+        )?)
+    }
+}
+
+/// Symbolic link reparse data, following the reparse data buffer header.
+///
+/// This is returned by [`GetReparsePointResponse::as_symlink`],
+/// and is also the tail of a [`SymbolicLinkErrorResponse`][crate::SymbolicLinkErrorResponse].
+///
+/// Reference: MS-FSCC 2.1.2.4
+#[smb_message_binrw]
+pub struct SymbolicLinkReparseData {
+    /// The offset, in bytes, of the substitute name in the path buffer.
+    pub substitute_name_offset: u16,
+    /// The length, in bytes, of the substitute name.
+    pub substitute_name_length: u16,
+    /// The offset, in bytes, of the print name in the path buffer.
+    pub print_name_offset: u16,
+    /// The length, in bytes, of the print name.
+    pub print_name_length: u16,
+    /// When set to [`SymbolicLinkReparseData::SYMLINK_FLAG_RELATIVE`], the substitute name is relative.
+    pub flags: u32,
+    /// The buffer containing the substitute and print names, in UTF-16.
+    #[br(parse_with = binrw::helpers::until_eof)]
+    pub path_buffer: Vec<u8>,
+}
+
+impl SymbolicLinkReparseData {
+    /// The size of the fields preceding the path buffer.
+    pub(crate) const HEADER_SIZE: usize = 12;
+
+    pub const SYMLINK_FLAG_RELATIVE: u32 = 0x00000001;
+
+    /// Returns the substitute name (the target path) of the symbolic link.
+    pub fn substitute_name(&self) -> String {
+        self.name_at(self.substitute_name_offset, self.substitute_name_length)
+    }
+
+    /// Returns the print name (the user-friendly target path) of the symbolic link.
+    pub fn print_name(&self) -> String {
+        self.name_at(self.print_name_offset, self.print_name_length)
+    }
+
+    /// Returns true if the substitute name is relative to the directory containing the link.
+    pub fn is_relative(&self) -> bool {
+        self.flags & Self::SYMLINK_FLAG_RELATIVE != 0
+    }
+
+    /// Decodes the UTF-16 name at `offset`, of `length` bytes, in the path buffer.
+    ///
+    /// Out-of-range names decode as empty.
+    fn name_at(&self, offset: u16, length: u16) -> String {
+        let start = offset as usize;
+        let end = start + length as usize;
+        let bytes = self.path_buffer.get(start..end).unwrap_or_default();
+        let chars = bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect::<Vec<_>>();
+        String::from_utf16_lossy(&chars)
+    }
+}
+
 #[smb_request_binrw]
 pub struct FileLevelTrimRequest {
     /// Key - reserved
@@ -694,13 +881,11 @@ impl IoctlRequestContent for OffloadReadRequest {
 pub struct OffloadReadResponse {
     #[bw(calc = 528)]
     #[br(assert(_size == 528))]
+    #[br(temp)]
     _size: u32,
 
-    // Note: this is a reduction of the flags field.
-    /// The data beyond the current range is logically equivalent to zero.
-    pub all_zero_beyond_current_range: Boolean,
-    _padding: u8,
-    _padding2: u16,
+    /// Flags describing the data represented by the token.
+    pub flags: OffloadReadFlags,
 
     /// contains the amount, in bytes, of data that the Token logically represents.
     /// This value indicates a contiguous region of the file from the beginning of the requested offset in the input.
@@ -713,8 +898,67 @@ pub struct OffloadReadResponse {
     pub token: [u8; 512], // TODO: Parse as STORAGE_OFFLOAD_TOKEN
 }
 
+impl OffloadReadResponse {
+    /// Returns true if the data beyond the current range is logically equivalent to zero.
+    pub fn all_zero_beyond_current_range(&self) -> bool {
+        self.flags.all_zero_beyond_current_range()
+    }
+}
+
 impl_fsctl_response!(OffloadRead, OffloadReadResponse);
 
+/// Flags for [`OffloadReadResponse`].
+///
+/// [MS-FSCC 2.3.42](https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-fscc/b98a8325-e6ec-464a-bc1b-8216b74f5828)
+#[smb_dtyp::mbitfield]
+pub struct OffloadReadFlags {
+    /// The data beyond the current range is logically equivalent to zero.
+    pub all_zero_beyond_current_range: bool,
+    #[skip]
+    __: B31,
+}
+
+/// Request for FSCTL_SET_ZERO_DATA, which sets a range of a file to zero.
+///
+/// On sparse files, the zeroed range may be deallocated.
+///
+/// Reference: MS-FSCC FSCTL_SET_ZERO_DATA Request (FILE_ZERO_DATA_INFORMATION)
+#[smb_request_binrw]
+pub struct SetZeroDataRequest {
+    /// The offset, in bytes, of the start of the range to set to zero.
+    pub file_offset: u64,
+    /// The offset, in bytes, of the first byte beyond the range to set to zero.
+    pub beyond_final_zero: u64,
+}
+
+impl IoctlRequestContent for SetZeroDataRequest {
+    fn get_bin_size(&self) -> u32 {
+        (size_of::<u64>() * 2) as u32
+    }
+}
+
+/// Request for FSCTL_DUPLICATE_EXTENTS_TO_FILE, which clones a range of a source file
+/// into the target file (the file of the IOCTL request), on file systems that support block cloning.
+///
+/// Reference: MS-FSCC FSCTL_DUPLICATE_EXTENTS_TO_FILE Request (DUPLICATE_EXTENTS_DATA)
+#[smb_request_binrw]
+pub struct DuplicateExtentsToFileRequest {
+    /// The file ID of the source file, which must be open on the same tree.
+    pub source_file_id: FileId,
+    /// The offset, in bytes, of the start of the range in the source file.
+    pub source_offset: u64,
+    /// The offset, in bytes, of the start of the range in the target file.
+    pub target_offset: u64,
+    /// The number of bytes to duplicate.
+    pub byte_count: u64,
+}
+
+impl IoctlRequestContent for DuplicateExtentsToFileRequest {
+    fn get_bin_size(&self) -> u32 {
+        (size_of::<FileId>() + size_of::<u64>() * 3) as u32
+    }
+}
+
 /// This macro wraps an existing type into a newtype that implements the `IoctlRequestContent` trait.
 /// It also provides a constructor and implements `From` and `Deref` traits for the new type.
 ///
@@ -777,6 +1021,7 @@ make_req_newtype!(pub PipePeekRequest(()));
 make_req_newtype!(pub SrvEnumerateSnapshotsRequest(()));
 make_req_newtype!(pub SrvRequestResumeKeyRequest(()));
 make_req_newtype!(pub QueryNetworkInterfaceInfoRequest(()));
+make_req_newtype!(pub GetReparsePointRequest(()));
 make_req_newtype!(pub PipeTransceiveRequest(IoctlBuffer));
 make_req_newtype!(pub SrvCopyChunkCopyWrite(SrvCopychunkCopy));
 
@@ -794,10 +1039,116 @@ make_res_newtype!(
     LmrRequestResiliency: pub LmrRequestResiliencyResponse(())
 );
 
+make_res_newtype!(
+    SetZeroData: pub SetZeroDataResponse(())
+);
+
+make_res_newtype!(
+    DuplicateExtentsToFile: pub DuplicateExtentsToFileResponse(())
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::negotiate::tests::make_response;
     use crate::*;
+    use smb_tests::hex_to_u8_array;
+
+    const SYMLINK_REPARSE_POINT_DATA: &str =
+        "0c0000a0240000000000100010000800000000005c003f003f005c0043003a005c00610043003a005c006100";
+
+    /// The symbolic link reparse point in [`SYMLINK_REPARSE_POINT_DATA`].
+    fn symlink_reparse_point_for_test() -> GetReparsePointResponse {
+        GetReparsePointResponse {
+            reparse_tag: ReparseTag::Symlink as u32,
+            reparse_guid: None,
+            // Skip the reparse tag, data length and reserved fields.
+            reparse_data: hex_to_u8_array! {SYMLINK_REPARSE_POINT_DATA}[8..].to_vec(),
+        }
+    }
+
+    test_binrw_response! {
+        GetReparsePointResponse: symlink_reparse_point_for_test() => SYMLINK_REPARSE_POINT_DATA
+    }
+
+    #[test]
+    fn test_get_reparse_point_as_symlink() {
+        let response = symlink_reparse_point_for_test();
+        assert_eq!(response.reparse_tag(), ReparseTag::Symlink as u32);
+        let symlink = response.as_symlink().unwrap();
+        assert_eq!(symlink.substitute_name(), "\\??\\C:\\a");
+        assert_eq!(symlink.print_name(), "C:\\a");
+        assert!(!symlink.is_relative());
+
+        let not_symlink = GetReparsePointResponse {
+            reparse_tag: 0xA0000003, // IO_REPARSE_TAG_MOUNT_POINT
+            ..response
+        };
+        assert!(not_symlink.as_symlink().is_err());
+    }
+
+    #[test]
+    fn test_plan_channels() {
+        let mut negotiate = make_response(
+            NegotiateDialect::Smb0302,
+            GlobalCapabilities::new().with_multi_channel(true),
+            None,
+        );
+        let interfaces = [
+            NetworkInterfaceInfo {
+                if_index: 2,
+                capability: NetworkInterfaceCapability::new().with_rss(true),
+                link_speed: 1000000000,
+                sockaddr: SocketAddrStorage::V4(SocketAddrStorageV4 {
+                    port: 0,
+                    address: 0xac10cc84u32.to_be(),
+                }),
+            },
+            NetworkInterfaceInfo {
+                if_index: 3,
+                capability: NetworkInterfaceCapability::new()
+                    .with_rss(true)
+                    .with_rdma(true),
+                link_speed: 10000000000,
+                sockaddr: SocketAddrStorage::V4(SocketAddrStorageV4 {
+                    port: 0,
+                    address: 0xac10cd84u32.to_be(),
+                }),
+            },
+            NetworkInterfaceInfo {
+                if_index: 4,
+                capability: NetworkInterfaceCapability::new(),
+                link_speed: 100000000000,
+                sockaddr: SocketAddrStorage::V4(SocketAddrStorageV4 {
+                    port: 0,
+                    address: 0xac10ce84u32.to_be(),
+                }),
+            },
+        ];
+
+        assert!(negotiate.supports_multichannel());
+        assert_eq!(
+            plan_channels(&negotiate, &interfaces),
+            vec![
+                ChannelPlan {
+                    if_index: 3,
+                    address: "172.16.205.132:0".parse().unwrap(),
+                    link_speed: 10000000000,
+                    rdma: true,
+                },
+                ChannelPlan {
+                    if_index: 2,
+                    address: "172.16.204.132:0".parse().unwrap(),
+                    link_speed: 1000000000,
+                    rdma: false,
+                },
+            ]
+        );
+
+        negotiate.dialect_revision = NegotiateDialect::Smb021;
+        assert!(!negotiate.supports_multichannel());
+        assert!(plan_channels(&negotiate, &interfaces).is_empty());
+    }
 
     test_binrw_request! {
         struct OffloadReadRequest {
@@ -808,6 +1159,21 @@ mod tests {
         } => "2000000000000000000000000000000000000000000000000000a00000000000"
     }
 
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_offload_read_response_all_zero() {
+        let mut data = smb_tests::hex_to_u8_array! {"10020000010000000010000000000000"};
+        data.extend([0xab; 512]);
+        let response = OffloadReadResponse::read_le(&mut std::io::Cursor::new(&data)).unwrap();
+        assert!(response.all_zero_beyond_current_range());
+        assert_eq!(response.transfer_length, 0x1000);
+        assert_eq!(response.token, [0xab; 512]);
+
+        data[4] = 0;
+        let response = OffloadReadResponse::read_le(&mut std::io::Cursor::new(&data)).unwrap();
+        assert!(!response.all_zero_beyond_current_range());
+    }
+
     test_binrw_response! {
         struct SrvRequestResumeKey {
             resume_key: [
@@ -818,6 +1184,26 @@ mod tests {
         } => "2d0300001c00000027116a2630d2db01fffe00000000000000000000"
     }
 
+    test_binrw_request! {
+        struct SetZeroDataRequest {
+            file_offset: 0x1000,
+            beyond_final_zero: 0x3000,
+        } => "00100000000000000030000000000000"
+    }
+
+    test_binrw_request! {
+        struct DuplicateExtentsToFileRequest {
+            source_file_id: [
+                0x28, 0x5, 0x0, 0x0, 0xc, 0x0, 0x0, 0x0, 0x85, 0x0, 0x0, 0x0, 0xc, 0x0, 0x0,
+                0x0,
+            ]
+            .into(),
+            source_offset: 0,
+            target_offset: 0x10000,
+            byte_count: 0x10000,
+        } => "280500000c000000850000000c000000000000000000000000000100000000000000010000000000"
+    }
+
     const CHUNK_SIZE: u32 = 1 << 20; // 1 MiB
     const TOTAL_SIZE: u32 = 10417096;
     const BLOCK_NUM: u32 = (TOTAL_SIZE + CHUNK_SIZE - 1) / CHUNK_SIZE;
@@ -847,6 +1233,36 @@ mod tests {
         0000000009000000000000000900000000000c8f30e0000000000"
     }
 
+    #[test]
+    fn test_srv_copychunk_same_file() {
+        let resume_key = SrvRequestResumeKey {
+            resume_key: [0x2d; SrvCopychunkCopy::SRV_KEY_LENGTH],
+            context: vec![],
+        };
+        let chunk = |source_offset, target_offset, length| SrvCopychunkItem {
+            source_offset,
+            target_offset,
+            length,
+        };
+
+        let req = SrvCopychunkCopy::same_file(
+            resume_key.resume_key,
+            vec![chunk(0, 0x2000, 0x1000), chunk(0x3000, 0x1000, 0x1000)],
+        )
+        .unwrap();
+        assert_eq!(req.source_key, resume_key.resume_key);
+        assert_eq!(req.chunks.len(), 2);
+
+        for bad in [
+            chunk(0, 0x1000, 0),
+            chunk(0, 0x800, 0x1000),
+            chunk(0x1000, 0x800, 0x1000),
+            chunk(u64::MAX, 0, 1),
+        ] {
+            assert!(SrvCopychunkCopy::same_file(resume_key.resume_key, vec![bad]).is_err());
+        }
+    }
+
     test_binrw_response! {
         struct SrvCopychunkResponse {
             chunks_written: 10,
@@ -14,7 +14,7 @@ use std::io::SeekFrom;
 
 use crate::{
     FileId,
-    dfsc::{ReqGetDfsReferral, ReqGetDfsReferralEx, RespGetDfsReferral},
+    dfsc::{ReferralLevel, ReqGetDfsReferral, ReqGetDfsReferralEx, RespGetDfsReferral},
 };
 
 /// SMB2 IOCTL request packet for issuing file system control or device control commands.
@@ -62,34 +62,147 @@ pub struct IoctlRequest {
     pub buffer: IoctlReqData,
 }
 
-#[cfg(all(feature = "client", not(feature = "server")))]
-/// This is a helper trait that defines, for a certain FSCTL request type,
-/// the response type and their matching FSCTL code.
-pub trait FsctlRequest: for<'a> BinWrite<Args<'a> = ()> + Into<IoctlReqData> {
-    type Response: FsctlResponseContent;
-    const FSCTL_CODE: FsctlCodes;
+impl IoctlRequest {
+    /// Default maximum output response size for requests built by this crate's helpers.
+    const DEFAULT_MAX_OUTPUT_RESPONSE: u32 = 1024;
+
+    /// Creates a device (non-FSCTL) IOCTL request, passing `input` as the raw input buffer.
+    ///
+    /// The FSCTL flag is cleared, and the server may return up to 1024 bytes of output;
+    /// adjust [`max_output_response`][Self::max_output_response] if more is expected.
+    pub fn new_ioctl(ctl_code: u32, file_id: FileId, input: Vec<u8>) -> Self {
+        Self {
+            ctl_code,
+            file_id,
+            max_input_response: 0,
+            max_output_response: Self::DEFAULT_MAX_OUTPUT_RESPONSE,
+            flags: IoctlRequestFlags::new().with_is_fsctl(false),
+            buffer: IoctlReqData::Ioctl(input.into()),
+        }
+    }
+
+    /// Creates an FSCTL_DFS_GET_REFERRALS request for `path`.
+    ///
+    /// The request targets [`FileId::FULL`], as is conventional for DFS referrals on `IPC$`.
+    /// Fails if `max_referral_level` is not a known [`ReferralLevel`].
+    pub fn dfs_referral(path: &str, max_referral_level: u16) -> crate::Result<Self> {
+        let max_referral_level = ReferralLevel::try_from(max_referral_level)
+            .map_err(|e| crate::SmbMsgError::InvalidData(e.to_string()))?;
+        Ok(Self {
+            ctl_code: FsctlCodes::DfsGetReferrals as u32,
+            file_id: FileId::FULL,
+            max_input_response: 0,
+            max_output_response: Self::DEFAULT_MAX_OUTPUT_RESPONSE,
+            flags: IoctlRequestFlags::new().with_is_fsctl(true),
+            buffer: ReqGetDfsReferral {
+                max_referral_level,
+                request_file_name: path.into(),
+            }
+            .into(),
+        })
+    }
 }
 
-#[cfg(all(feature = "server", not(feature = "client")))]
+/// A builder for [`IoctlRequest`]s. Use [`IoctlRequest::builder`] to create one.
+///
+/// The maximum input response size defaults to 0, and the maximum output response size
+/// defaults to [`FsctlRequest::DEFAULT_MAX_OUTPUT_RESPONSE`] for FSCTLs, or to 1024 bytes for device IOCTLs.
+#[derive(Debug)]
+pub struct IoctlRequestBuilder {
+    file_id: FileId,
+    request: Option<(u32, IoctlRequestFlags, IoctlReqData, u32)>,
+    max_input_response: u32,
+    max_output_response: Option<u32>,
+}
+
+impl IoctlRequest {
+    /// Returns a builder for an IOCTL request on `file_id`.
+    pub fn builder(file_id: FileId) -> IoctlRequestBuilder {
+        IoctlRequestBuilder {
+            file_id,
+            request: None,
+            max_input_response: 0,
+            max_output_response: None,
+        }
+    }
+}
+
+impl IoctlRequestBuilder {
+    /// Sets the FSCTL request to send. The control code and the FSCTL flag are set accordingly.
+    pub fn fsctl<T: FsctlRequest>(mut self, request: T) -> Self {
+        self.request = Some((
+            T::FSCTL_CODE as u32,
+            IoctlRequestFlags::new().with_is_fsctl(true),
+            request.into(),
+            T::DEFAULT_MAX_OUTPUT_RESPONSE,
+        ));
+        self
+    }
+
+    /// Sets a device IOCTL request to send, with `input` as the raw input buffer.
+    pub fn ioctl(mut self, ctl_code: u32, input: Vec<u8>) -> Self {
+        self.request = Some((
+            ctl_code,
+            IoctlRequestFlags::new().with_is_fsctl(false),
+            IoctlReqData::Ioctl(input.into()),
+            IoctlRequest::DEFAULT_MAX_OUTPUT_RESPONSE,
+        ));
+        self
+    }
+
+    /// Sets the maximum number of bytes the server may return in the output buffer.
+    pub fn max_output(mut self, max_output_response: u32) -> Self {
+        self.max_output_response = Some(max_output_response);
+        self
+    }
+
+    /// Sets the maximum number of bytes the server may return in the input buffer.
+    pub fn max_input(mut self, max_input_response: u32) -> Self {
+        self.max_input_response = max_input_response;
+        self
+    }
+
+    /// Builds the request. Fails if neither [`fsctl`][Self::fsctl] nor [`ioctl`][Self::ioctl] was called.
+    pub fn build(self) -> crate::Result<IoctlRequest> {
+        let (ctl_code, flags, buffer, default_max_output) = self.request.ok_or_else(|| {
+            crate::SmbMsgError::InvalidData("IOCTL request has no FSCTL or IOCTL set".into())
+        })?;
+        Ok(IoctlRequest {
+            ctl_code,
+            file_id: self.file_id,
+            max_input_response: self.max_input_response,
+            max_output_response: self.max_output_response.unwrap_or(default_max_output),
+            flags,
+            buffer,
+        })
+    }
+}
+
+macro_rules! fsctl_request_trait {
+    ($($bounds:tt)+) => {
 /// This is a helper trait that defines, for a certain FSCTL request type,
 /// the response type and their matching FSCTL code.
-pub trait FsctlRequest: for<'a> BinRead<Args<'a> = ()> + Into<IoctlReqData> {
+pub trait FsctlRequest: $($bounds)+ {
     type Response: FsctlResponseContent;
     const FSCTL_CODE: FsctlCodes;
+    /// The default maximum output response size for this FSCTL,
+    /// used by [`IoctlRequestBuilder::fsctl`]. Zero for FSCTLs that return no output.
+    const DEFAULT_MAX_OUTPUT_RESPONSE: u32;
+}
+    };
 }
 
+#[cfg(all(feature = "client", not(feature = "server")))]
+fsctl_request_trait!(for<'a> BinWrite<Args<'a> = ()> + Into<IoctlReqData>);
+#[cfg(all(feature = "server", not(feature = "client")))]
+fsctl_request_trait!(for<'a> BinRead<Args<'a> = ()> + Into<IoctlReqData>);
 #[cfg(all(feature = "server", feature = "client"))]
-/// This is a helper trait that defines, for a certain FSCTL request type,
-/// the response type and their matching FSCTL code.
-pub trait FsctlRequest:
+fsctl_request_trait!(
     for<'a> BinWrite<Args<'a> = ()> + for<'b> BinRead<Args<'b> = ()> + Into<IoctlReqData>
-{
-    type Response: FsctlResponseContent;
-    const FSCTL_CODE: FsctlCodes;
-}
+);
 
 macro_rules! ioctl_req_data {
-    ($($fsctl:ident: $model:ty, $response:ty, )+) => {
+    ($($fsctl:ident: $model:ty, $response:ty $(=> $max_output:expr)?, )+) => {
         pastey::paste! {
 
 #[smb_request_binrw]
@@ -105,10 +218,10 @@ pub enum IoctlReqData {
         [<Fsctl $fsctl:camel>]($model),
     )+
 
-    /// General, non-smb FSCTL ioctl buffer.
+    /// General, raw ioctl buffer.
     ///
-    /// In case of an unsupported FSCTL code, this variant can be used to
-    /// pass raw bytes.
+    /// Used for device IOCTLs (where `flags.is_fsctl()` is false),
+    /// and in case of an unsupported FSCTL code, to pass raw bytes.
     Ioctl(IoctlBuffer),
 }
 
@@ -128,6 +241,7 @@ $(
     impl FsctlRequest for $model {
         type Response = $response;
         const FSCTL_CODE: FsctlCodes = FsctlCodes::$fsctl;
+        const DEFAULT_MAX_OUTPUT_RESPONSE: u32 = ioctl_req_data!(@max_output $($max_output)?);
     }
 
     impl From<$model> for IoctlReqData {
@@ -137,28 +251,36 @@ $(
     }
 )+
         }
-    }
+    };
+    (@max_output) => {
+        IoctlRequest::DEFAULT_MAX_OUTPUT_RESPONSE
+    };
+    (@max_output $max_output:expr) => {
+        $max_output
+    };
 }
 
-// TODO: Enable non-fsctl ioctls. currently, we only support FSCTLs.
 ioctl_req_data! {
     PipePeek: PipePeekRequest, PipePeekResponse,
     SrvEnumerateSnapshots: SrvEnumerateSnapshotsRequest, SrvEnumerateSnapshotsResponse,
     SrvRequestResumeKey: SrvRequestResumeKeyRequest, SrvRequestResumeKey,
-    QueryNetworkInterfaceInfo: QueryNetworkInterfaceInfoRequest, NetworkInterfacesInfo,
+    QueryNetworkInterfaceInfo: QueryNetworkInterfaceInfoRequest, NetworkInterfacesInfo => 0x10000,
     SrvCopychunk: SrvCopychunkCopy, SrvCopychunkResponse,
     SrvCopychunkWrite: SrvCopyChunkCopyWrite, SrvCopychunkResponse,
     SrvReadHash: SrvReadHashReq, SrvReadHashRes,
-    LmrRequestResiliency: NetworkResiliencyRequest, LmrRequestResiliencyResponse,
+    LmrRequestResiliency: NetworkResiliencyRequest, LmrRequestResiliencyResponse => 0,
     ValidateNegotiateInfo: ValidateNegotiateInfoRequest, ValidateNegotiateInfoResponse,
     DfsGetReferrals: ReqGetDfsReferral, RespGetDfsReferral,
-    PipeWait: PipeWaitRequest, PipeWaitResponse,
+    PipeWait: PipeWaitRequest, PipeWaitResponse => 0,
     PipeTransceive: PipeTransceiveRequest, PipeTransceiveResponse,
-    SetReparsePoint: SetReparsePointRequest, SetReparsePointResponse,
+    SetReparsePoint: SetReparsePointRequest, SetReparsePointResponse => 0,
+    GetReparsePoint: GetReparsePointRequest, GetReparsePointResponse => 0x4000,
     DfsGetReferralsEx: ReqGetDfsReferralEx, RespGetDfsReferral,
     FileLevelTrim: FileLevelTrimRequest, FileLevelTrimResponse,
     QueryAllocatedRanges: QueryAllocRangesItem, QueryAllocRangesResult,
     OffloadRead: OffloadReadRequest, OffloadReadResponse,
+    SetZeroData: SetZeroDataRequest, SetZeroDataResponse => 0,
+    DuplicateExtentsToFile: DuplicateExtentsToFileRequest, DuplicateExtentsToFileResponse => 0,
 }
 
 /// Flags field indicating how to process the IOCTL operation.
@@ -191,14 +313,14 @@ pub struct IoctlResponse {
     #[br(temp)]
     input_offset: PosMarker<u32>,
     /// Should be set to zero (exception for pass-through operations)
-    #[bw(assert(in_buffer.is_empty()))] // there is an exception for pass-through operations.
     #[bw(try_calc = in_buffer.len().try_into())]
-    #[br(assert(input_count == 0))]
     #[br(temp)]
     input_count: u32,
 
     /// Offset to output data buffer (either 0 or input_offset + input_count rounded to multiple of 8)
-    #[br(assert(output_offset.value == 0 || output_offset.value == input_offset.value + input_count))]
+    #[br(assert(output_offset.value == 0
+        || output_offset.value == input_offset.value + input_count
+        || output_offset.value == (input_offset.value + input_count).next_multiple_of(8)))]
     #[bw(calc = PosMarker::default())]
     #[br(temp)]
     output_offset: PosMarker<u32>,
@@ -212,7 +334,9 @@ pub struct IoctlResponse {
 
     reserved: u32,
 
-    /// Input data buffer (typically empty for responses except pass-through operations)
+    /// Input data buffer.
+    ///
+    /// Empty for responses, except for pass-through operations, see [`IoctlResponse::is_passthrough`].
     #[br(seek_before = SeekFrom::Start(input_offset.value.into()))]
     #[br(count = input_count)]
     #[bw(write_with = PosMarker::write_aoff, args(&input_offset))]
@@ -220,12 +344,48 @@ pub struct IoctlResponse {
 
     /// Output data buffer containing results of the FSCTL/IOCTL operation
     #[br(seek_before = SeekFrom::Start(output_offset.value.into()))]
+    #[bw(align_before = 8)]
     #[br(count = output_count)]
     #[bw(write_with = PosMarker::write_aoff, args(&output_offset))]
     pub out_buffer: Vec<u8>,
 }
 
 impl IoctlResponse {
+    /// Creates a response to a pass-through operation, echoing `in_buffer` back to the client.
+    ///
+    /// The output buffer is placed after the input buffer, aligned to 8 bytes.
+    pub fn new_passthrough(
+        ctl_code: u32,
+        file_id: FileId,
+        in_buffer: Vec<u8>,
+        out_buffer: Vec<u8>,
+    ) -> Self {
+        Self {
+            ctl_code,
+            file_id,
+            in_buffer,
+            out_buffer,
+        }
+    }
+
+    /// Returns the input data buffer of the response.
+    ///
+    /// This is empty, unless the response is for a pass-through operation.
+    pub fn in_buffer(&self) -> &[u8] {
+        &self.in_buffer
+    }
+
+    /// Returns the output data buffer of the response.
+    pub fn out_buffer(&self) -> &[u8] {
+        &self.out_buffer
+    }
+
+    /// Whether this is a response to a pass-through operation,
+    /// which is the only case where the input buffer is non-empty.
+    pub fn is_passthrough(&self) -> bool {
+        !self.in_buffer.is_empty()
+    }
+
     #[cfg(feature = "client")]
     /// Parses the FSCTL response output buffer into the specified response type.
     ///
@@ -277,6 +437,110 @@ mod tests {
         } => const_format::concatcp!("3900000017c01100280500000c000000850000000c0000007800000098000000000000000000000000000000000400000100000000000000", REQ_IOCTL_BUFFER_CONTENT)
     }
 
+    /// A made-up device IOCTL code: `CTL_CODE(FILE_DEVICE_UNKNOWN, 0x801, METHOD_BUFFERED, FILE_ANY_ACCESS)`
+    const TEST_DEVICE_CTL_CODE: u32 = 0x00222004;
+
+    test_request! {
+        device_ioctl: Ioctl {
+            ctl_code: TEST_DEVICE_CTL_CODE,
+            file_id: [
+                0x28, 0x5, 0x0, 0x0, 0xc, 0x0, 0x0, 0x0, 0x85, 0x0, 0x0, 0x0, 0xc, 0x0, 0x0,
+                0x0,
+            ]
+            .into(),
+            max_input_response: 0,
+            max_output_response: 1024,
+            flags: IoctlRequestFlags::new(),
+            buffer: IoctlReqData::Ioctl(vec![0x01, 0x02, 0x03, 0x04].into()),
+        } => "3900000004202200280500000c000000850000000c000000780000000400000000000000000000000000000000040000000000000000000001020304"
+    }
+
+    #[test]
+    fn test_ioctl_request_new_ioctl() {
+        let file_id = FileId::from([
+            0x28, 0x5, 0x0, 0x0, 0xc, 0x0, 0x0, 0x0, 0x85, 0x0, 0x0, 0x0, 0xc, 0x0, 0x0, 0x0,
+        ]);
+        let request = IoctlRequest::new_ioctl(TEST_DEVICE_CTL_CODE, file_id, vec![1, 2, 3, 4]);
+        assert!(!request.flags.is_fsctl());
+        assert_eq!(request.ctl_code, TEST_DEVICE_CTL_CODE);
+        assert_eq!(request.file_id, file_id);
+        assert_eq!(request.buffer.get_size(), 4);
+        assert!(matches!(request.buffer, IoctlReqData::Ioctl(ref data) if **data == [1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn test_ioctl_request_dfs_referral() {
+        let request = IoctlRequest::dfs_referral(r"\domain\dfsroot", 4).unwrap();
+        assert_eq!(request.ctl_code, FsctlCodes::DfsGetReferrals as u32);
+        assert_eq!(request.file_id, FileId::FULL);
+        assert!(request.flags.is_fsctl());
+        assert_eq!(
+            request.buffer,
+            IoctlReqData::FsctlDfsGetReferrals(ReqGetDfsReferral {
+                max_referral_level: ReferralLevel::V4,
+                request_file_name: r"\domain\dfsroot".into(),
+            })
+        );
+
+        assert!(matches!(
+            IoctlRequest::dfs_referral(r"\domain\dfsroot", 5),
+            Err(SmbMsgError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_ioctl_request_builder() {
+        let request = IoctlRequest::builder(FileId::FULL)
+            .fsctl(QueryNetworkInterfaceInfoRequest(()))
+            .max_output(64 * 1024)
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.ctl_code,
+            FsctlCodes::QueryNetworkInterfaceInfo as u32
+        );
+        assert!(request.flags.is_fsctl());
+        assert_eq!(request.file_id, FileId::FULL);
+        assert_eq!(request.max_input_response, 0);
+        assert_eq!(request.max_output_response, 0x10000);
+        assert_eq!(
+            request.buffer,
+            IoctlReqData::FsctlQueryNetworkInterfaceInfo(QueryNetworkInterfaceInfoRequest(()))
+        );
+
+        // Per-FSCTL defaults.
+        assert_eq!(
+            IoctlRequest::builder(FileId::FULL)
+                .fsctl(QueryNetworkInterfaceInfoRequest(()))
+                .build()
+                .unwrap()
+                .max_output_response,
+            0x10000
+        );
+        assert_eq!(
+            IoctlRequest::builder(FileId::EMPTY)
+                .fsctl(SetZeroDataRequest {
+                    file_offset: 0,
+                    beyond_final_zero: 0x1000,
+                })
+                .build()
+                .unwrap()
+                .max_output_response,
+            0
+        );
+
+        let request = IoctlRequest::builder(FileId::EMPTY)
+            .ioctl(TEST_DEVICE_CTL_CODE, vec![1, 2, 3, 4])
+            .build()
+            .unwrap();
+        assert_eq!(
+            request,
+            IoctlRequest::new_ioctl(TEST_DEVICE_CTL_CODE, FileId::EMPTY, vec![1, 2, 3, 4])
+        );
+
+        assert!(IoctlRequest::builder(FileId::EMPTY).build().is_err());
+    }
+
     // Just to make things pretty; do NOT edit.
     const IOCTL_TEST_BUFFER_CONTENT: &'static str = "05000203100000000401000003000000ec00000001000000000002000000000001000000000000000000020000000000200000000000000001000000000000000c000e000000000000000200000000000000020000000000070000000000000000000000000000000600000000000000410056004900560056004d00000000000400000000000000010400000000000515000000173da72e955653f915dff28001000000000000000000020000000000010000000000000001000000000000000a000c00000000000000020000000000000000000000000006000000000000000000000000000000050000000000000061007600690076006e0000000100000000000000";
 
@@ -292,4 +556,75 @@ mod tests {
                 out_buffer: smb_tests::hex_to_u8_array! {IOCTL_TEST_BUFFER_CONTENT},
         } => const_format::concatcp!("3100000017c01100280500000c000000850000000c000000700000000000000070000000040100000000000000000000",IOCTL_TEST_BUFFER_CONTENT)
     }
+
+    test_response! {
+        passthrough: Ioctl {
+            ctl_code: FsctlCodes::PipeTransceive as u32,
+            file_id: [
+                0x28, 0x5, 0x0, 0x0, 0xc, 0x0, 0x0, 0x0, 0x85, 0x0, 0x0, 0x0, 0xc, 0x0, 0x0,
+                0x0,
+            ]
+            .into(),
+            in_buffer: vec![0x01, 0x02, 0x03, 0x04],
+            out_buffer: vec![0x05, 0x06, 0x07, 0x08],
+        } => "3100000017c01100280500000c000000850000000c0000007000000004000000780000000400000000000000000000000102030400000000
+        05060708"
+    }
+
+    test_response! {
+        passthrough_unaligned: Ioctl {
+            ctl_code: FsctlCodes::PipeTransceive as u32,
+            file_id: [
+                0x28, 0x5, 0x0, 0x0, 0xc, 0x0, 0x0, 0x0, 0x85, 0x0, 0x0, 0x0, 0xc, 0x0, 0x0,
+                0x0,
+            ]
+            .into(),
+            in_buffer: vec![0x01, 0x02, 0x03],
+            out_buffer: vec![0x05, 0x06, 0x07, 0x08],
+        } => "3100000017c01100280500000c000000850000000c000000700000000300000078000000040000000000000000000000010203
+        000000000005060708"
+    }
+
+    // An output buffer that immediately follows the input buffer, without padding, is also accepted.
+    test_response_read! {
+        passthrough_unpadded: Ioctl {
+            ctl_code: FsctlCodes::PipeTransceive as u32,
+            file_id: [
+                0x28, 0x5, 0x0, 0x0, 0xc, 0x0, 0x0, 0x0, 0x85, 0x0, 0x0, 0x0, 0xc, 0x0, 0x0,
+                0x0,
+            ]
+            .into(),
+            in_buffer: vec![0x01, 0x02, 0x03],
+            out_buffer: vec![0x05, 0x06, 0x07, 0x08],
+        } => "3100000017c01100280500000c000000850000000c0000007000000003000000730000000400000000000000000000000102030506070800"
+    }
+
+    #[test]
+    fn test_ioctl_response_new_passthrough() {
+        let response = IoctlResponse::new_passthrough(
+            FsctlCodes::PipeTransceive as u32,
+            FileId::EMPTY,
+            vec![0x01, 0x02, 0x03],
+            vec![0x05],
+        );
+        assert!(response.is_passthrough());
+        assert_eq!(response.in_buffer(), &[0x01, 0x02, 0x03]);
+        assert_eq!(response.out_buffer(), &[0x05]);
+    }
+
+    #[test]
+    fn test_ioctl_response_passthrough() {
+        let mut response = IoctlResponse {
+            ctl_code: FsctlCodes::PipeTransceive as u32,
+            file_id: FileId::EMPTY,
+            in_buffer: vec![],
+            out_buffer: vec![0x05, 0x06],
+        };
+        assert!(!response.is_passthrough());
+        assert_eq!(response.out_buffer(), &[0x05, 0x06]);
+
+        response.in_buffer = vec![0x01];
+        assert!(response.is_passthrough());
+        assert_eq!(response.in_buffer(), &[0x01]);
+    }
 }
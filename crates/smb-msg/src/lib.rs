@@ -54,9 +54,6 @@ use thiserror::Error;
 /// SMB Message related errors
 #[derive(Error, Debug)]
 pub enum SmbMsgError {
-    #[error("Error code definition not found for NT Status code: {0:#x}")]
-    MissingErrorCodeDefinition(u32),
-
     #[error("FSCTL definition not found for FSCTL code: {0:#x}")]
     MissingFsctlDefinition(u32),
 
@@ -71,6 +68,13 @@ pub enum SmbMsgError {
     #[error("Invalid data: {0}")]
     InvalidData(String),
 
+    /// This error is returned when an info class is paired with data of a different info type.
+    #[error("Info class {class} does not match info data {data}")]
+    InfoClassMismatch {
+        class: &'static str,
+        data: &'static str,
+    },
+
     #[error("Invalid negotiate dialect cast to dialect: {0:?}")]
     InvalidDialect(NegotiateDialect),
 
@@ -15,6 +15,7 @@ pub struct LockRequest {
     /// Number of SMB2_LOCK_ELEMENT structures in the locks array.
     /// Must be greater than or equal to 1.
     #[bw(try_calc = locks.len().try_into())]
+    #[br(temp)]
     lock_count: u16,
     /// Lock sequence information for the request.
     pub lock_sequence: LockSequence,
@@ -25,6 +26,14 @@ pub struct LockRequest {
     pub locks: Vec<LockElement>,
 }
 
+impl LockRequest {
+    /// Sets the lock sequence of the request, and returns it.
+    pub fn with_sequence(mut self, lock_sequence: LockSequence) -> Self {
+        self.lock_sequence = lock_sequence;
+        self
+    }
+}
+
 /// Lock sequence information containing sequence number and index.
 /// In SMB 2.0.2 dialect, this field is unused and must be reserved.
 /// In all other dialects, contains sequence number and index fields.
@@ -38,6 +47,34 @@ pub struct LockSequence {
     pub index: B28,
 }
 
+impl LockSequence {
+    /// The maximum valid value of [`LockSequence::number`].
+    pub const MAX_NUMBER: u8 = 0xf;
+    /// The maximum valid value of [`LockSequence::index`].
+    pub const MAX_INDEX: u32 = 64;
+
+    /// Creates a new lock sequence from a sequence number and index.
+    ///
+    /// Fails if `number` exceeds [`LockSequence::MAX_NUMBER`] or `index` exceeds [`LockSequence::MAX_INDEX`].
+    ///
+    /// _Note:_ [`LockSequence::new`] is the bitfield's default (zeroed) constructor.
+    pub fn try_new(number: u8, index: u32) -> crate::Result<Self> {
+        if number > Self::MAX_NUMBER {
+            return Err(crate::SmbMsgError::InvalidData(format!(
+                "Lock sequence number {number} exceeds {}",
+                Self::MAX_NUMBER
+            )));
+        }
+        if index > Self::MAX_INDEX {
+            return Err(crate::SmbMsgError::InvalidData(format!(
+                "Lock sequence index {index} exceeds {}",
+                Self::MAX_INDEX
+            )));
+        }
+        Ok(Self::new().with_number(number).with_index(index))
+    }
+}
+
 /// SMB2_LOCK_ELEMENT structure used to indicate segments of files
 /// that are locked or unlocked in SMB2 LOCK requests.
 ///
@@ -85,6 +122,52 @@ pub struct LockResponse {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::*;
+
+    test_request! {
+        Lock {
+            lock_sequence: LockSequence::new().with_number(1).with_index(2),
+            file_id: FileId {
+                persistent: 0x1234,
+                volatile: 0x5678,
+            },
+            locks: vec![LockElement {
+                offset: 0x1000,
+                length: 0x200,
+                flags: LockFlag::new().with_exclusive(true),
+            }],
+        } => "300001002100000034120000000000007856000000000000001000000000000000020000000000000200000000000000"
+    }
+
+    test_response! {
+        Lock {} => "04000000"
+    }
+
+    #[test]
+    fn test_lock_sequence_valid() {
+        let sequence = LockSequence::try_new(15, 64).unwrap();
+        assert_eq!(sequence.number(), 15);
+        assert_eq!(sequence.index(), 64);
+        assert_eq!(u32::from_le_bytes(sequence.into_bytes()), (64 << 4) | 15);
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_lock_request_with_sequence() {
+        let sequence = LockSequence::try_new(1, 2).unwrap();
+        let request = LockRequest {
+            lock_sequence: LockSequence::new(),
+            file_id: FileId::EMPTY,
+            locks: vec![],
+        }
+        .with_sequence(sequence);
+        assert_eq!(request.lock_sequence, sequence);
+    }
 
-    // TODO: tests
+    #[test]
+    fn test_lock_sequence_out_of_range() {
+        assert!(LockSequence::try_new(16, 1).is_err());
+        assert!(LockSequence::try_new(1, 65).is_err());
+    }
 }
@@ -14,6 +14,17 @@ macro_rules! make_message {
             Encrypted($crate::EncryptedMessage),
             Compressed($crate::CompressedMessage),
         }
+
+        impl $name {
+            /// Returns the [`MessageKind`] of this message.
+            pub fn kind(&self) -> MessageKind {
+                match self {
+                    $name::Plain(_) => MessageKind::Plain,
+                    $name::Encrypted(_) => MessageKind::Encrypted,
+                    $name::Compressed(_) => MessageKind::Compressed,
+                }
+            }
+        }
     };
 }
 
@@ -27,3 +38,161 @@ impl TryFrom<&[u8]> for Response {
         Response::read(&mut std::io::Cursor::new(value))
     }
 }
+
+#[cfg(feature = "client")]
+impl Request {
+    /// Serializes the request into `buf`, replacing its previous contents.
+    ///
+    /// The buffer's allocation is reused, which is useful for avoiding
+    /// reallocations when sending many messages.
+    pub fn write_into(&self, buf: &mut Vec<u8>) -> crate::Result<()> {
+        buf.clear();
+        self.write(&mut std::io::Cursor::new(buf))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "client")]
+impl Response {
+    /// Size of the direct TCP transport frame header, in bytes.
+    pub const TRANSPORT_FRAME_HEADER_SIZE: usize = 4;
+
+    /// Reads a single response from the start of `buf`, which is framed by
+    /// the 4-byte big-endian length prefix of the direct TCP transport.
+    ///
+    /// Returns the parsed response, and the number of bytes consumed from `buf`,
+    /// including the length prefix. If `buf` does not yet contain the entire frame,
+    /// an [`UnexpectedEof`][std::io::ErrorKind::UnexpectedEof] I/O error is returned,
+    /// so the caller may read more data and try again.
+    pub fn from_transport_frame(buf: &[u8]) -> Result<(Response, usize), binrw::Error> {
+        let incomplete = || {
+            binrw::Error::Io(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "Incomplete transport frame",
+            ))
+        };
+        let length = buf
+            .get(..Self::TRANSPORT_FRAME_HEADER_SIZE)
+            .ok_or_else(incomplete)?;
+        let length = u32::from_be_bytes(length.try_into().unwrap()) as usize;
+        let frame_end = Self::TRANSPORT_FRAME_HEADER_SIZE + length;
+        let frame = buf
+            .get(Self::TRANSPORT_FRAME_HEADER_SIZE..frame_end)
+            .ok_or_else(incomplete)?;
+        Ok((Response::try_from(frame)?, frame_end))
+    }
+}
+
+/// The kind of an SMB2 message, as indicated by its 4-byte protocol identifier.
+///
+/// Each kind matches a variant of the [`Request`] and [`Response`] enums.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MessageKind {
+    /// A plain message, starting with `\xFESMB`.
+    Plain,
+    /// An encrypted (transformed) message, starting with `\xFDSMB`.
+    Encrypted,
+    /// A compressed message, starting with `\xFCSMB`.
+    Compressed,
+}
+
+impl MessageKind {
+    /// Size of the protocol identifier, in bytes.
+    pub const PROTOCOL_ID_SIZE: usize = 4;
+
+    /// Returns the protocol identifier for this message kind.
+    pub const fn protocol_id(&self) -> &'static [u8; Self::PROTOCOL_ID_SIZE] {
+        match self {
+            MessageKind::Plain => b"\xfeSMB",
+            MessageKind::Encrypted => b"\xfdSMB",
+            MessageKind::Compressed => b"\xfcSMB",
+        }
+    }
+}
+
+/// Returns the [`MessageKind`] of the message in `bytes`, by peeking at its protocol identifier.
+///
+/// `bytes` should begin right after the NetBIOS header.
+/// Returns `None` if the buffer is too short, or does not start with a known SMB2 protocol identifier.
+pub fn peek_message_kind(bytes: &[u8]) -> Option<MessageKind> {
+    let protocol_id = bytes.get(..MessageKind::PROTOCOL_ID_SIZE)?;
+    [
+        MessageKind::Plain,
+        MessageKind::Encrypted,
+        MessageKind::Compressed,
+    ]
+    .into_iter()
+    .find(|kind| kind.protocol_id() == protocol_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peek_message_kind() {
+        assert_eq!(
+            peek_message_kind(b"\xfeSMB\x40\x00"),
+            Some(MessageKind::Plain)
+        );
+        assert_eq!(peek_message_kind(b"\xfdSMB"), Some(MessageKind::Encrypted));
+        assert_eq!(
+            peek_message_kind(b"\xfcSMB\x00\x00"),
+            Some(MessageKind::Compressed)
+        );
+        assert_eq!(peek_message_kind(b"\xffSMB\x72"), None);
+        assert_eq!(peek_message_kind(b"GET / HTTP/1.1"), None);
+        assert_eq!(peek_message_kind(b"\xfeSM"), None);
+        assert_eq!(peek_message_kind(&[]), None);
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_request_write_into() {
+        use crate::{EchoRequest, PlainRequest};
+
+        let echo = || Request::Plain(PlainRequest::new(EchoRequest::default().into()));
+        let mut buf = vec![0xff; 256];
+        echo().write_into(&mut buf).unwrap();
+        let first = buf.clone();
+        assert_eq!(
+            first.len(),
+            crate::Header::STRUCT_SIZE + EchoRequest::PAYLOAD_SIZE
+        );
+
+        let capacity = buf.capacity();
+        echo().write_into(&mut buf).unwrap();
+        assert_eq!(buf, first);
+        assert_eq!(buf.capacity(), capacity);
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_response_from_transport_frame() {
+        use crate::{Command, ResponseContent};
+
+        let echo_response = smb_tests::hex_to_u8_array! {
+            "fe534d4240000000000000000d0001000100000000000000050000000000000000000000
+            0000000000000000000000000000000000000000000000000000000004000000"
+        };
+        let mut buf = (echo_response.len() as u32).to_be_bytes().to_vec();
+        buf.extend_from_slice(&echo_response);
+        buf.extend_from_slice(b"\x00\x00"); // start of the next frame
+
+        let (response, consumed) = Response::from_transport_frame(&buf).unwrap();
+        assert_eq!(consumed, 4 + echo_response.len());
+        let Response::Plain(plain) = response else {
+            panic!("Expected a plain response");
+        };
+        assert_eq!(plain.header.command, Command::Echo);
+        assert_eq!(plain.header.message_id, 5);
+        assert!(matches!(plain.content, ResponseContent::Echo(_)));
+
+        for partial in [&buf[..2], &buf[..consumed - 1]] {
+            assert!(matches!(
+                Response::from_transport_frame(partial),
+                Err(binrw::Error::Io(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof
+            ));
+        }
+    }
+}
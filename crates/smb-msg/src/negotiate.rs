@@ -41,6 +41,131 @@ pub struct NegotiateRequest {
     pub negotiate_context_list: Option<Vec<NegotiateContext>>,
 }
 
+impl NegotiateRequest {
+    /// Whether the client indicates DFS support.
+    pub fn wants_dfs(&self) -> bool {
+        self.capabilities.dfs()
+    }
+
+    /// Whether the client indicates file leasing support.
+    pub fn wants_leasing(&self) -> bool {
+        self.capabilities.leasing()
+    }
+
+    /// Whether the client indicates large MTU (multiple credit operations) support.
+    pub fn wants_large_mtu(&self) -> bool {
+        self.capabilities.large_mtu()
+    }
+
+    /// Whether the client indicates multi-channel support.
+    pub fn wants_multichannel(&self) -> bool {
+        self.capabilities.multi_channel()
+    }
+
+    /// Whether the client indicates persistent handles support.
+    pub fn wants_persistent_handles(&self) -> bool {
+        self.capabilities.persistent_handles()
+    }
+
+    /// Whether the client indicates directory leasing support.
+    pub fn wants_directory_leasing(&self) -> bool {
+        self.capabilities.directory_leasing()
+    }
+
+    /// Whether the client indicates encryption support.
+    pub fn wants_encryption(&self) -> bool {
+        self.capabilities.encryption()
+    }
+
+    /// Whether the client indicates change notifications support.
+    pub fn wants_notifications(&self) -> bool {
+        self.capabilities.notifications()
+    }
+
+    /// Validates the dialect list: it must not be empty, and must not contain duplicates.
+    ///
+    /// MS-SMB2 does not require any ordering; use [`NegotiateRequest::dialects_ascending`]
+    /// to additionally check that the dialects are listed in ascending order.
+    ///
+    /// Reference: MS-SMB2 2.2.3, 3.3.5.4
+    pub fn validate_dialects(&self) -> crate::Result<()> {
+        if self.dialects.is_empty() {
+            return Err(crate::SmbMsgError::InvalidData(
+                "Negotiate request has no dialects".into(),
+            ));
+        }
+        for (i, dialect) in self.dialects.iter().enumerate() {
+            if self.dialects[..i].contains(dialect) {
+                return Err(crate::SmbMsgError::InvalidData(format!(
+                    "Negotiate request dialect {dialect:?} appears more than once"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the dialects are listed in strictly ascending order.
+    pub fn dialects_ascending(&self) -> bool {
+        self.dialects.is_sorted_by(|a, b| a < b)
+    }
+
+    /// Returns the highest dialect offered by the client, or `None` if the dialect list is empty.
+    pub fn highest_dialect(&self) -> Option<Dialect> {
+        self.dialects.iter().copied().max()
+    }
+
+    /// Validates the negotiate context list against the SMB 3.1.1 requirements:
+    /// - A [`PreauthIntegrityCapabilities`] context must be present, and must be the first context.
+    /// - If the client indicates encryption support, an [`EncryptionCapabilities`] context must be present.
+    /// - Each known context type must not appear more than once.
+    ///
+    /// Requests that do not offer [`Dialect::Smb0311`] are always valid.
+    ///
+    /// Reference: MS-SMB2 2.2.3.1, 3.3.5.4
+    pub fn validate_311_contexts(&self) -> crate::Result<()> {
+        if !self.dialects.contains(&Dialect::Smb0311) {
+            return Ok(());
+        }
+        let contexts = self.negotiate_context_list.as_deref().unwrap_or_default();
+
+        match contexts.first().map(|ctx| ctx.context_type) {
+            Some(NegotiateContextType::PreauthIntegrityCapabilities) => {}
+            _ if self.get_ctx_preauth_integrity_capabilities().is_some() => {
+                return Err(crate::SmbMsgError::InvalidData(
+                    "SMB 3.1.1 preauth integrity context must be the first negotiate context"
+                        .into(),
+                ));
+            }
+            _ => {
+                return Err(crate::SmbMsgError::InvalidData(
+                    "SMB 3.1.1 negotiate request is missing the preauth integrity context".into(),
+                ));
+            }
+        }
+
+        if self.wants_encryption() && self.get_ctx_encryption_capabilities().is_none() {
+            return Err(crate::SmbMsgError::InvalidData(
+                "SMB 3.1.1 negotiate request indicates encryption support, but is missing the encryption context".into(),
+            ));
+        }
+
+        for (i, ctx) in contexts.iter().enumerate() {
+            if !matches!(ctx.context_type, NegotiateContextType::Unknown(_))
+                && contexts[..i]
+                    .iter()
+                    .any(|prev| prev.context_type == ctx.context_type)
+            {
+                return Err(crate::SmbMsgError::InvalidData(format!(
+                    "SMB 3.1.1 negotiate context {:?} appears more than once",
+                    ctx.context_type
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Flags for SMB2 negotiation security mode.
 ///
 /// See [NegotiateSecurityMode].
@@ -85,6 +210,32 @@ pub struct GlobalCapabilities {
     __: B24,
 }
 
+impl GlobalCapabilities {
+    /// Returns capabilities with all the defined capabilities set.
+    pub fn all() -> Self {
+        Self::new()
+            .with_dfs(true)
+            .with_leasing(true)
+            .with_large_mtu(true)
+            .with_multi_channel(true)
+            .with_persistent_handles(true)
+            .with_directory_leasing(true)
+            .with_encryption(true)
+            .with_notifications(true)
+    }
+
+    /// Returns the capabilities set in both `self` and `other`.
+    pub fn intersect(&self, other: &GlobalCapabilities) -> GlobalCapabilities {
+        let bits = u32::from_le_bytes(self.into_bytes()) & u32::from_le_bytes(other.into_bytes());
+        Self::from_bytes(bits.to_le_bytes())
+    }
+
+    /// Whether all the capabilities set in `other` are also set in `self`.
+    pub fn contains(&self, other: &GlobalCapabilities) -> bool {
+        self.intersect(other) == *other
+    }
+}
+
 /// SMB2 NEGOTIATE Response.
 ///
 /// Sent by the server to notify the client of the preferred common dialect.
@@ -135,10 +286,73 @@ pub struct NegotiateResponse {
     pub negotiate_context_list: Option<Vec<NegotiateContext>>,
 }
 
+impl NegotiateResponse {
+    /// Returns the dialect selected by the server.
+    ///
+    /// Fails with [`SmbMsgError::InvalidDialect`][crate::SmbMsgError::InvalidDialect]
+    /// if the server returned the SMB 2.0 wildcard revision.
+    pub fn selected_dialect(&self) -> crate::Result<Dialect> {
+        Dialect::try_from(self.dialect_revision)
+    }
+
+    /// Whether the server supports multi-channel: the [`GlobalCapabilities::multi_channel`]
+    /// capability is set, and the selected dialect supports [`Feature::Multichannel`].
+    pub fn supports_multichannel(&self) -> bool {
+        self.capabilities.multi_channel()
+            && self
+                .selected_dialect()
+                .is_ok_and(|dialect| dialect.supports(Feature::Multichannel))
+    }
+
+    /// Gets the salt from the [`PreauthIntegrityCapabilities`] context, if present.
+    pub fn get_ctx_preauth_salt(&self) -> Option<&[u8]> {
+        self.get_ctx_preauth_integrity_capabilities()
+            .map(|caps| caps.salt.as_slice())
+    }
+
+    /// Gets the RDMA transform IDs from the [`RdmaTransformCapabilities`] context, if present.
+    pub fn get_ctx_rdma_transforms(&self) -> Option<&Vec<RdmaTransformId>> {
+        self.get_ctx_rdma_transform_capabilities()
+            .map(|caps| &caps.transforms)
+    }
+
+    /// Gets the [`TransportCapabilities`] context, if present.
+    pub fn get_ctx_transport(&self) -> Option<&TransportCapabilities> {
+        self.get_ctx_transport_capabilities()
+    }
+
+    /// Gets the server name from the [`NetnameNegotiateContextId`] context, if present.
+    pub fn get_ctx_netname(&self) -> Option<&SizedWideString> {
+        self.get_ctx_netname_negotiate_context_id()
+            .map(|ctx| &ctx.netname)
+    }
+
+    /// Returns the encryption cipher negotiated with the server, if any.
+    ///
+    /// - For SMB 3.1.1, this is the cipher selected in the [`EncryptionCapabilities`] context.
+    /// - For SMB 3.0 and 3.0.2, encryption is indicated by the
+    ///   [`GlobalCapabilities::encryption`] bit, and implies [`EncryptionCipher::Aes128Ccm`].
+    pub fn encryption_negotiated(&self) -> Option<EncryptionCipher> {
+        match self.dialect_revision {
+            NegotiateDialect::Smb0311 => self
+                .get_ctx_encryption_capabilities()
+                .and_then(|caps| caps.ciphers.first().copied()),
+            NegotiateDialect::Smb030 | NegotiateDialect::Smb0302
+                if self.capabilities.encryption() =>
+            {
+                Some(EncryptionCipher::Aes128Ccm)
+            }
+            _ => None,
+        }
+    }
+}
+
 /// SMB2/SMB3 protocol dialect revisions.
 ///
 /// Reference: MS-SMB2 2.2.3
-#[derive(BinRead, BinWrite, Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
+#[derive(
+    BinRead, BinWrite, Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, smb_dtyp::ReprEnum,
+)]
 #[brw(repr(u16))]
 pub enum Dialect {
     Smb0202 = 0x0202,
@@ -164,6 +378,54 @@ impl Dialect {
     pub fn is_smb3(&self) -> bool {
         self >= &Dialect::Smb030
     }
+
+    /// Returns the highest dialect in `client` that does not exceed `server_max`,
+    /// or `None` if there is no such dialect.
+    pub fn best_common(client: &[Dialect], server_max: Dialect) -> Option<Dialect> {
+        client.iter().copied().filter(|d| *d <= server_max).max()
+    }
+
+    /// Returns the minimum dialect required for `feature`.
+    pub fn min_for(feature: Feature) -> Dialect {
+        match feature {
+            Feature::Leasing => Dialect::Smb021,
+            Feature::Encryption
+            | Feature::Multichannel
+            | Feature::PersistentHandles
+            | Feature::DirectoryLeasing => Dialect::Smb030,
+            Feature::Compression | Feature::TransportCapabilities => Dialect::Smb0311,
+        }
+    }
+
+    /// Whether this dialect supports `feature`.
+    ///
+    /// Note that this only checks the dialect; the server must still advertise the
+    /// matching capability (or negotiate context) for the feature to be usable.
+    #[inline]
+    pub fn supports(&self, feature: Feature) -> bool {
+        *self >= Self::min_for(feature)
+    }
+}
+
+/// Protocol features that require a minimum [`Dialect`]. See [`Dialect::supports`].
+///
+/// Reference: MS-SMB2 2.2.3, 2.2.4
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Feature {
+    /// Leasing (SMB 2.1+).
+    Leasing,
+    /// Encryption (SMB 3.0+).
+    Encryption,
+    /// Multichannel (SMB 3.0+).
+    Multichannel,
+    /// Persistent handles (SMB 3.0+).
+    PersistentHandles,
+    /// Directory leasing (SMB 3.0+).
+    DirectoryLeasing,
+    /// Compression (SMB 3.1.1).
+    Compression,
+    /// Transport capabilities negotiate context (SMB 3.1.1).
+    TransportCapabilities,
 }
 
 /// Dialects that may be used in the SMB Negotiate Response.
@@ -171,7 +433,7 @@ impl Dialect {
 /// The same as [Dialect] but includes a wildcard revision for SMB 2.0.
 ///
 /// Reference: MS-SMB2 2.2.4
-#[derive(BinRead, BinWrite, Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(BinRead, BinWrite, Debug, PartialEq, Eq, Clone, Copy, smb_dtyp::ReprEnum)]
 #[brw(repr(u16))]
 pub enum NegotiateDialect {
     Smb0202 = Dialect::Smb0202 as isize,
@@ -227,22 +489,67 @@ pub struct NegotiateContext {
     pub data: NegotiateContextValue,
 }
 
+impl NegotiateContext {
+    /// Creates a negotiate context with the given raw type and data, which is written verbatim.
+    ///
+    /// This is mostly useful for testing, e.g. injecting unsupported or malformed contexts.
+    /// Note that when reading the context back, a known `context_type` is parsed into its typed value.
+    pub fn raw(context_type: u16, data: Vec<u8>) -> Self {
+        NegotiateContext {
+            context_type: NegotiateContextType::from(context_type),
+            data: NegotiateContextValue::Unknown {
+                ctx_type: context_type,
+                data,
+            },
+        }
+    }
+}
+
 macro_rules! negotiate_context_type {
     ($($name:ident = $id:literal,)+) => {
 /// Negotiate context type identifiers.
 ///
+/// Context types that are not defined here are parsed as [`NegotiateContextType::Unknown`].
+///
 /// Reference: MS-SMB2 2.2.3.1
-#[derive(BinRead, BinWrite, Debug, PartialEq, Eq)]
-#[brw(repr(u16))]
+#[binrw::binrw]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[br(map = |x: u16| Self::from(x))]
+#[bw(map = |x: &Self| u16::from(*x))]
 pub enum NegotiateContextType {
     $(
-        $name = $id,
+        $name,
     )+
+    /// A context type that is not defined in this enum.
+    Unknown(u16),
+}
+
+impl From<u16> for NegotiateContextType {
+    fn from(value: u16) -> Self {
+        match value {
+            $(
+                $id => NegotiateContextType::$name,
+            )+
+            _ => NegotiateContextType::Unknown(value),
+        }
+    }
+}
+
+impl From<NegotiateContextType> for u16 {
+    fn from(value: NegotiateContextType) -> Self {
+        match value {
+            $(
+                NegotiateContextType::$name => $id,
+            )+
+            NegotiateContextType::Unknown(value) => value,
+        }
+    }
 }
 
 /// Negotiate context values.
 ///
 /// Each variant corresponds to a specific negotiate context type.
+/// Contexts of an unknown type are kept as raw bytes in [`NegotiateContextValue::Unknown`].
 #[derive(BinRead, BinWrite, Debug, PartialEq, Eq)]
 #[br(import(context_type: &NegotiateContextType))]
 pub enum NegotiateContextValue {
@@ -250,6 +557,17 @@ pub enum NegotiateContextValue {
         #[br(pre_assert(context_type == &NegotiateContextType::$name))]
         $name($name),
     )+
+    /// A context of a type that is not supported by this crate.
+    #[br(pre_assert(matches!(context_type, NegotiateContextType::Unknown(_))))]
+    Unknown {
+        /// The raw context type identifier.
+        #[br(calc = u16::from(*context_type))]
+        #[bw(ignore)]
+        ctx_type: u16,
+        /// The raw context data.
+        #[br(parse_with = binrw::helpers::until_eof)]
+        data: Vec<u8>,
+    },
 }
 
 impl NegotiateContextValue {
@@ -261,6 +579,9 @@ impl NegotiateContextValue {
                     NegotiateContextType::$name
                 }
             )+
+            NegotiateContextValue::Unknown { ctx_type, .. } => {
+                NegotiateContextType::Unknown(*ctx_type)
+            }
         }
     }
 }
@@ -323,7 +644,7 @@ negotiate_context_type!(
 /// Hash algorithms for pre-authentication integrity.
 ///
 /// Reference: MS-SMB2 2.2.3.1.1
-#[derive(BinRead, BinWrite, Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(BinRead, BinWrite, Debug, PartialEq, Eq, Clone, Copy, smb_dtyp::ReprEnum)]
 #[brw(repr(u16))]
 pub enum HashAlgorithm {
     Sha512 = 0x01,
@@ -348,6 +669,22 @@ pub struct PreauthIntegrityCapabilities {
     pub salt: Vec<u8>,
 }
 
+impl PreauthIntegrityCapabilities {
+    /// Creates a new [`PreauthIntegrityCapabilities`] using [`HashAlgorithm::Sha512`],
+    /// with a random salt of `len` bytes.
+    #[cfg(feature = "rand")]
+    pub fn new_with_random_salt(len: usize) -> Self {
+        use rand::{RngCore, rngs::OsRng};
+
+        let mut salt = vec![0u8; len];
+        OsRng.fill_bytes(&mut salt);
+        Self {
+            hash_algorithms: vec![HashAlgorithm::Sha512],
+            salt,
+        }
+    }
+}
+
 /// (Context) Encryption capabilities.
 ///
 /// Specifies the encryption ciphers supported by the client or server.
@@ -365,7 +702,7 @@ pub struct EncryptionCapabilities {
 /// Encryption cipher identifiers.
 ///
 /// Reference: MS-SMB2 2.2.3.1.2
-#[derive(BinRead, BinWrite, Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(BinRead, BinWrite, Debug, PartialEq, Eq, Clone, Copy, smb_dtyp::ReprEnum)]
 #[brw(repr(u16))]
 pub enum EncryptionCipher {
     Aes128Ccm = 0x0001,
@@ -396,7 +733,7 @@ pub struct CompressionCapabilities {
 /// Compression algorithm identifiers.
 ///
 /// Reference: MS-SMB2 2.2.3.1.3
-#[derive(BinRead, BinWrite, Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(BinRead, BinWrite, Debug, PartialEq, Eq, Clone, Copy, smb_dtyp::ReprEnum)]
 #[brw(repr(u16))]
 #[repr(u16)]
 pub enum CompressionAlgorithm {
@@ -496,6 +833,8 @@ pub struct RdmaTransformCapabilities {
 /// Reference: MS-SMB2 2.2.3.1.6
 #[smb_message_binrw]
 #[brw(repr(u16))]
+#[repr(u16)]
+#[derive(smb_dtyp::ReprEnum)]
 pub enum RdmaTransformId {
     None = 0x0000,
     Encryption = 0x0001,
@@ -519,7 +858,7 @@ pub struct SigningCapabilities {
 /// Signing algorithm identifiers.
 ///
 /// Reference: MS-SMB2 2.2.3.1.7
-#[derive(BinRead, BinWrite, Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(BinRead, BinWrite, Debug, PartialEq, Eq, Clone, Copy, smb_dtyp::ReprEnum)]
 #[brw(repr(u16))]
 pub enum SigningAlgorithmId {
     HmacSha256 = 0x0000,
@@ -528,7 +867,7 @@ pub enum SigningAlgorithmId {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use smb_dtyp::make_guid;
     use smb_tests::hex_to_u8_array;
     use time::macros::datetime;
@@ -536,8 +875,9 @@ mod tests {
     use super::*;
     use crate::*;
 
-    test_request! {
-        Negotiate {
+    /// Negotiate request fixture, matching the bytes of the request test below.
+    fn negotiate_request() -> NegotiateRequest {
+        NegotiateRequest {
             security_mode: NegotiateSecurityMode::new().with_signing_enabled(true),
             capabilities: GlobalCapabilities::new()
                 .with_dfs(true)
@@ -559,14 +899,20 @@ mod tests {
             negotiate_context_list: Some(vec![
                 PreauthIntegrityCapabilities {
                     hash_algorithms: vec![HashAlgorithm::Sha512],
-                    salt: hex_to_u8_array! {"ed006c304e332890b2bd98617b5ad9ef075994154673696280ffcc0f1291a15d"}
-                }.into(),
-                EncryptionCapabilities { ciphers: vec![
-                    EncryptionCipher::Aes128Gcm,
-                    EncryptionCipher::Aes128Ccm,
-                    EncryptionCipher::Aes256Gcm,
-                    EncryptionCipher::Aes256Ccm,
-                ] }.into(),
+                    salt: hex_to_u8_array! {
+                        "ed006c304e332890b2bd98617b5ad9ef075994154673696280ffcc0f1291a15d"
+                    },
+                }
+                .into(),
+                EncryptionCapabilities {
+                    ciphers: vec![
+                        EncryptionCipher::Aes128Gcm,
+                        EncryptionCipher::Aes128Ccm,
+                        EncryptionCipher::Aes256Gcm,
+                        EncryptionCipher::Aes256Ccm,
+                    ],
+                }
+                .into(),
                 CompressionCapabilities {
                     flags: CompressionCapsFlags::new().with_chained(true),
                     compression_algorithms: vec![
@@ -575,17 +921,45 @@ mod tests {
                         CompressionAlgorithm::LZ77Huffman,
                         CompressionAlgorithm::LZNT1,
                         CompressionAlgorithm::LZ4,
-                    ]
-                }.into(),
-                SigningCapabilities { signing_algorithms: vec![
-                    SigningAlgorithmId::AesGmac,
-                    SigningAlgorithmId::AesCmac,
-                    SigningAlgorithmId::HmacSha256,
-                ] }.into(),
-                NetnameNegotiateContextId { netname: "localhost".into() }.into(),
-                RdmaTransformCapabilities { transforms: vec![RdmaTransformId::Encryption, RdmaTransformId::Signing] }.into()
-            ])
-        } => "2400050001000000ff000000df0d2ec1dd43f0118b87000c298
+                    ],
+                }
+                .into(),
+                SigningCapabilities {
+                    signing_algorithms: vec![
+                        SigningAlgorithmId::AesGmac,
+                        SigningAlgorithmId::AesCmac,
+                        SigningAlgorithmId::HmacSha256,
+                    ],
+                }
+                .into(),
+                NetnameNegotiateContextId {
+                    netname: "localhost".into(),
+                }
+                .into(),
+                RdmaTransformCapabilities {
+                    transforms: vec![RdmaTransformId::Encryption, RdmaTransformId::Signing],
+                }
+                .into(),
+            ]),
+        }
+    }
+
+    /// Builds a request on top of the fixture, with the given dialects, capabilities and contexts.
+    fn make_request(
+        dialects: Vec<Dialect>,
+        capabilities: GlobalCapabilities,
+        negotiate_context_list: Option<Vec<NegotiateContext>>,
+    ) -> NegotiateRequest {
+        NegotiateRequest {
+            dialects,
+            capabilities,
+            negotiate_context_list,
+            ..negotiate_request()
+        }
+    }
+
+    test_request! {
+        Negotiate = negotiate_request() => "2400050001000000ff000000df0d2ec1dd43f0118b87000c298
         016827000000006000000020210020003020311030000010026000000
         0000010020000100ed006c304e332890b2bd98617b5ad9ef075994154
         673696280ffcc0f1291a15d000002000a000000000004000200010004
@@ -595,13 +969,66 @@ mod tests {
         000000007000c0000000000020000000000000001000200"
     }
 
-    test_response! {
-        Negotiate {
+    #[test]
+    fn test_validate_311_contexts() {
+        let preauth = || -> NegotiateContext {
+            PreauthIntegrityCapabilities {
+                hash_algorithms: vec![HashAlgorithm::Sha512],
+                salt: vec![0; 32],
+            }
+            .into()
+        };
+        let make_encryption = || -> NegotiateContext {
+            EncryptionCapabilities {
+                ciphers: vec![EncryptionCipher::Aes128Gcm],
+            }
+            .into()
+        };
+        let with_contexts = |contexts: Vec<NegotiateContext>| {
+            make_request(
+                vec![Dialect::Smb0302, Dialect::Smb0311],
+                GlobalCapabilities::new().with_encryption(true),
+                Some(contexts),
+            )
+        };
+
+        let valid = with_contexts(vec![preauth(), make_encryption()]);
+        assert!(valid.validate_311_contexts().is_ok());
+
+        let missing_preauth = with_contexts(vec![make_encryption()]);
+        assert!(matches!(
+            missing_preauth.validate_311_contexts(),
+            Err(SmbMsgError::InvalidData(msg)) if msg.contains("missing the preauth")
+        ));
+
+        let wrong_order = with_contexts(vec![make_encryption(), preauth()]);
+        assert!(matches!(
+            wrong_order.validate_311_contexts(),
+            Err(SmbMsgError::InvalidData(msg)) if msg.contains("must be the first")
+        ));
+
+        let missing_encryption = with_contexts(vec![preauth()]);
+        assert!(missing_encryption.validate_311_contexts().is_err());
+
+        let duplicate = with_contexts(vec![preauth(), make_encryption(), make_encryption()]);
+        assert!(duplicate.validate_311_contexts().is_err());
+
+        let no_311 = make_request(
+            vec![Dialect::Smb0302],
+            GlobalCapabilities::new().with_encryption(true),
+            None,
+        );
+        assert!(no_311.validate_311_contexts().is_ok());
+    }
+
+    /// Negotiate response fixture, matching the bytes of the response test below.
+    pub(crate) fn negotiate_response() -> NegotiateResponse {
+        NegotiateResponse {
             security_mode: NegotiateSecurityMode::new().with_signing_enabled(true),
             dialect_revision: NegotiateDialect::Smb0311,
             server_guid: Guid::from([
-                0xb9, 0x21, 0xf8, 0xe0, 0x15, 0x7, 0xaa, 0x41, 0xbe, 0x38, 0x67, 0xfe, 0xbf,
-                0x5e, 0x2e, 0x11
+                0xb9, 0x21, 0xf8, 0xe0, 0x15, 0x7, 0xaa, 0x41, 0xbe, 0x38, 0x67, 0xfe, 0xbf, 0x5e,
+                0x2e, 0x11,
             ]),
             capabilities: GlobalCapabilities::new()
                 .with_dfs(true)
@@ -615,43 +1042,325 @@ mod tests {
             system_time: datetime!(2025-01-18 16:24:39.448746400).into(),
             server_start_time: FileTime::default(),
             buffer: [
-                0x60, 0x28, 0x6, 0x6, 0x2b, 0x6, 0x1, 0x5, 0x5, 0x2, 0xa0, 0x1e, 0x30, 0x1c,
-                0xa0, 0x1a, 0x30, 0x18, 0x6, 0xa, 0x2b, 0x6, 0x1, 0x4, 0x1, 0x82, 0x37, 0x2,
-                0x2, 0x1e, 0x6, 0xa, 0x2b, 0x6, 0x1, 0x4, 0x1, 0x82, 0x37, 0x2, 0x2, 0xa
+                0x60, 0x28, 0x6, 0x6, 0x2b, 0x6, 0x1, 0x5, 0x5, 0x2, 0xa0, 0x1e, 0x30, 0x1c, 0xa0,
+                0x1a, 0x30, 0x18, 0x6, 0xa, 0x2b, 0x6, 0x1, 0x4, 0x1, 0x82, 0x37, 0x2, 0x2, 0x1e,
+                0x6, 0xa, 0x2b, 0x6, 0x1, 0x4, 0x1, 0x82, 0x37, 0x2, 0x2, 0xa,
             ]
             .to_vec(),
             negotiate_context_list: Some(vec![
                 PreauthIntegrityCapabilities {
-                        hash_algorithms: vec![HashAlgorithm::Sha512],
-                        salt: [
-                            0xd5, 0x67, 0x1b, 0x24, 0xa1, 0xe9, 0xcc, 0xc8, 0x93, 0xf5, 0x55,
-                            0x5a, 0x31, 0x3, 0x43, 0x5a, 0x85, 0x2b, 0xc3, 0xcb, 0x1a, 0xd3,
-                            0x2d, 0xc5, 0x1f, 0x92, 0x80, 0x6e, 0xf3, 0xfb, 0x4d, 0xd4
-                        ]
-                        .to_vec()
-                    }
+                    hash_algorithms: vec![HashAlgorithm::Sha512],
+                    salt: [
+                        0xd5, 0x67, 0x1b, 0x24, 0xa1, 0xe9, 0xcc, 0xc8, 0x93, 0xf5, 0x55, 0x5a,
+                        0x31, 0x3, 0x43, 0x5a, 0x85, 0x2b, 0xc3, 0xcb, 0x1a, 0xd3, 0x2d, 0xc5,
+                        0x1f, 0x92, 0x80, 0x6e, 0xf3, 0xfb, 0x4d, 0xd4,
+                    ]
+                    .to_vec(),
+                }
                 .into(),
                 EncryptionCapabilities {
-                    ciphers: vec![EncryptionCipher::Aes128Gcm]
+                    ciphers: vec![EncryptionCipher::Aes128Gcm],
                 }
                 .into(),
                 SigningCapabilities {
-                    signing_algorithms: vec![SigningAlgorithmId::AesGmac]
+                    signing_algorithms: vec![SigningAlgorithmId::AesGmac],
                 }
                 .into(),
                 RdmaTransformCapabilities {
-                    transforms: vec![RdmaTransformId::Encryption, RdmaTransformId::Signing]
+                    transforms: vec![RdmaTransformId::Encryption, RdmaTransformId::Signing],
                 }
                 .into(),
                 CompressionCapabilities {
                     flags: CompressionCapsFlags::new().with_chained(true),
                     compression_algorithms: vec![
                         CompressionAlgorithm::LZ77,
-                        CompressionAlgorithm::PatternV1
-                    ]
+                        CompressionAlgorithm::PatternV1,
+                    ],
                 }
                 .into(),
-            ])
-        } => "4100010011030500b921f8e01507aa41be3867febf5e2e112f000000000080000000800000008000a876d878c569db01000000000000000080002a00b0000000602806062b0601050502a01e301ca01a3018060a2b06010401823702021e060a2b06010401823702020a0000000000000100260000000000010020000100d5671b24a1e9ccc893f5555a3103435a852bc3cb1ad32dc51f92806ef3fb4dd40000020004000000000001000200000000000800040000000000010002000000000007000c00000000000200000000000000010002000000000003000c0000000000020000000100000002000400"
+            ]),
+        }
+    }
+
+    /// Builds a response on top of the fixture, with the given dialect, capabilities and contexts.
+    pub(crate) fn make_response(
+        dialect_revision: NegotiateDialect,
+        capabilities: GlobalCapabilities,
+        negotiate_context_list: Option<Vec<NegotiateContext>>,
+    ) -> NegotiateResponse {
+        NegotiateResponse {
+            dialect_revision,
+            capabilities,
+            negotiate_context_list,
+            ..negotiate_response()
+        }
+    }
+
+    test_response! {
+        Negotiate = negotiate_response() => "4100010011030500b921f8e01507aa41be3867febf5e2e112f000000000080000000800000008000a876d878c569db01000000000000000080002a00b0000000602806062b0601050502a01e301ca01a3018060a2b06010401823702021e060a2b06010401823702020a0000000000000100260000000000010020000100d5671b24a1e9ccc893f5555a3103435a852bc3cb1ad32dc51f92806ef3fb4dd40000020004000000000001000200000000000800040000000000010002000000000007000c00000000000200000000000000010002000000000003000c0000000000020000000100000002000400"
+    }
+
+    #[test]
+    fn test_global_capabilities_intersect() {
+        let client = GlobalCapabilities::all();
+        let server = GlobalCapabilities::new()
+            .with_dfs(true)
+            .with_leasing(true)
+            .with_encryption(true);
+        let agreed = client.intersect(&server);
+        assert_eq!(agreed, server);
+        assert!(client.contains(&agreed));
+        assert!(!server.contains(&client));
+        assert!(server.contains(&GlobalCapabilities::new()));
+        assert_eq!(
+            server.intersect(&GlobalCapabilities::new().with_multi_channel(true)),
+            GlobalCapabilities::new()
+        );
+        assert_eq!(
+            u32::from_le_bytes(GlobalCapabilities::all().into_bytes()),
+            0xff
+        );
+    }
+
+    #[test]
+    fn test_dialect_best_common() {
+        let client = [Dialect::Smb0202, Dialect::Smb030, Dialect::Smb0311];
+        assert_eq!(
+            Dialect::best_common(&client, Dialect::Smb0311),
+            Some(Dialect::Smb0311)
+        );
+        assert_eq!(
+            Dialect::best_common(&client, Dialect::Smb0302),
+            Some(Dialect::Smb030)
+        );
+        assert_eq!(Dialect::best_common(&client[1..], Dialect::Smb021), None);
+        assert_eq!(Dialect::best_common(&[], Dialect::MAX), None);
+    }
+
+    #[test]
+    fn test_negotiate_request_validate_dialects() {
+        let mut request = make_request(
+            vec![Dialect::Smb0202, Dialect::Smb021, Dialect::Smb0311],
+            GlobalCapabilities::new(),
+            None,
+        );
+        assert!(request.validate_dialects().is_ok());
+        assert!(request.dialects_ascending());
+        assert_eq!(request.highest_dialect(), Some(Dialect::Smb0311));
+
+        request.dialects = vec![Dialect::Smb030, Dialect::Smb0202, Dialect::Smb030];
+        assert!(request.validate_dialects().is_err());
+        assert!(!request.dialects_ascending());
+        assert_eq!(request.highest_dialect(), Some(Dialect::Smb030));
+
+        request.dialects = vec![];
+        assert!(request.validate_dialects().is_err());
+        assert_eq!(request.highest_dialect(), None);
+    }
+
+    #[test]
+    fn test_dialect_supports() {
+        assert!(!Dialect::Smb021.supports(Feature::Encryption));
+        assert!(Dialect::Smb030.supports(Feature::Encryption));
+        assert!(!Dialect::Smb0202.supports(Feature::Leasing));
+        assert!(Dialect::Smb021.supports(Feature::Leasing));
+        assert!(Dialect::Smb0302.supports(Feature::PersistentHandles));
+        assert!(!Dialect::Smb0302.supports(Feature::Compression));
+        assert!(Dialect::Smb0311.supports(Feature::TransportCapabilities));
+    }
+
+    #[test]
+    fn test_negotiate_request_capability_accessors() {
+        let mut request = negotiate_request();
+        assert!(request.wants_dfs());
+        assert!(request.wants_leasing());
+        assert!(request.wants_large_mtu());
+        assert!(request.wants_multichannel());
+        assert!(request.wants_persistent_handles());
+        assert!(request.wants_directory_leasing());
+        assert!(request.wants_encryption());
+        assert!(request.wants_notifications());
+
+        request.capabilities = GlobalCapabilities::new().with_encryption(true);
+        assert!(request.wants_encryption());
+        assert!(!request.wants_multichannel());
+        assert!(!request.wants_dfs());
+    }
+
+    #[test]
+    fn test_negotiate_response_selected_dialect() {
+        let mut response =
+            make_response(NegotiateDialect::Smb0302, GlobalCapabilities::new(), None);
+        assert_eq!(response.selected_dialect().unwrap(), Dialect::Smb0302);
+
+        response.dialect_revision = NegotiateDialect::Smb02Wildcard;
+        assert!(matches!(
+            response.selected_dialect(),
+            Err(SmbMsgError::InvalidDialect(NegotiateDialect::Smb02Wildcard))
+        ));
+    }
+
+    #[test]
+    fn test_negotiate_response_preauth_salt() {
+        let response = make_response(
+            NegotiateDialect::Smb0311,
+            GlobalCapabilities::new(),
+            Some(vec![
+                PreauthIntegrityCapabilities {
+                    hash_algorithms: vec![HashAlgorithm::Sha512],
+                    salt: vec![1, 2, 3, 4],
+                }
+                .into(),
+            ]),
+        );
+        assert_eq!(
+            response.get_ctx_preauth_salt(),
+            Some([1, 2, 3, 4].as_slice())
+        );
+    }
+
+    #[test]
+    fn test_negotiate_response_encryption_negotiated() {
+        let mut response = make_response(
+            NegotiateDialect::Smb0302,
+            GlobalCapabilities::new().with_encryption(true),
+            None,
+        );
+        assert_eq!(
+            response.encryption_negotiated(),
+            Some(EncryptionCipher::Aes128Ccm)
+        );
+
+        response.capabilities = GlobalCapabilities::new();
+        assert_eq!(response.encryption_negotiated(), None);
+
+        response.dialect_revision = NegotiateDialect::Smb0311;
+        response.negotiate_context_list = Some(vec![
+            PreauthIntegrityCapabilities {
+                hash_algorithms: vec![HashAlgorithm::Sha512],
+                salt: vec![1, 2, 3, 4],
+            }
+            .into(),
+        ]);
+        assert_eq!(response.encryption_negotiated(), None);
+
+        response.negotiate_context_list.as_mut().unwrap().push(
+            EncryptionCapabilities {
+                ciphers: vec![EncryptionCipher::Aes256Gcm],
+            }
+            .into(),
+        );
+        assert_eq!(
+            response.encryption_negotiated(),
+            Some(EncryptionCipher::Aes256Gcm)
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_preauth_random_salt() {
+        let caps = PreauthIntegrityCapabilities::new_with_random_salt(32);
+        assert_eq!(caps.hash_algorithms, vec![HashAlgorithm::Sha512]);
+        assert_eq!(caps.salt.len(), 32);
+        assert_ne!(caps, PreauthIntegrityCapabilities::new_with_random_salt(32));
+    }
+
+    #[test]
+    fn test_negotiate_response_transport_contexts() {
+        let mut response = make_response(
+            NegotiateDialect::Smb0311,
+            GlobalCapabilities::new(),
+            Some(vec![]),
+        );
+        assert!(response.get_ctx_rdma_transforms().is_none());
+        assert!(response.get_ctx_transport().is_none());
+        assert!(response.get_ctx_netname().is_none());
+
+        response.negotiate_context_list = Some(vec![
+            RdmaTransformCapabilities {
+                transforms: vec![RdmaTransformId::Encryption],
+            }
+            .into(),
+            TransportCapabilities::new()
+                .with_accept_transport_layer_security(true)
+                .into(),
+            NetnameNegotiateContextId {
+                netname: "server".into(),
+            }
+            .into(),
+        ]);
+        assert_eq!(
+            response.get_ctx_rdma_transforms(),
+            Some(&vec![RdmaTransformId::Encryption])
+        );
+        assert!(
+            response
+                .get_ctx_transport()
+                .unwrap()
+                .accept_transport_layer_security()
+        );
+        assert_eq!(response.get_ctx_netname().unwrap(), &"server");
+    }
+
+    smb_tests::test_binrw! {
+        struct NegotiateContext => unknown {
+            context_type: NegotiateContextType::Unknown(0x00ff),
+            data: NegotiateContextValue::Unknown {
+                ctx_type: 0x00ff,
+                data: vec![0x01, 0x02, 0x03, 0x04],
+            },
+        } => "ff0004000000000001020304"
+    }
+
+    #[test]
+    fn test_negotiate_context_raw() {
+        let ctx = NegotiateContext::raw(0x1234, vec![0xaa, 0xbb, 0xcc]);
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        ctx.write_le(&mut cursor).unwrap();
+        assert_eq!(
+            cursor.get_ref().as_slice(),
+            &[
+                0x34, 0x12, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0xaa, 0xbb, 0xcc
+            ]
+        );
+
+        cursor.set_position(0);
+        let read = NegotiateContext::read_le(&mut cursor).unwrap();
+        assert_eq!(read, ctx);
+        assert_eq!(read.context_type, NegotiateContextType::Unknown(0x1234));
+
+        // Known types are written verbatim, but parsed into their typed value.
+        let ctx = NegotiateContext::raw(0x0005, vec![0x73, 0x00]);
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        ctx.write_le(&mut cursor).unwrap();
+        cursor.set_position(0);
+        let read = NegotiateContext::read_le(&mut cursor).unwrap();
+        assert_eq!(
+            read.data,
+            NegotiateContextValue::NetnameNegotiateContextId(NetnameNegotiateContextId {
+                netname: "s".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_negotiate_context_type_unknown() {
+        assert_eq!(
+            NegotiateContextType::from(0x0007),
+            NegotiateContextType::RdmaTransformCapabilities
+        );
+        assert_eq!(
+            NegotiateContextType::from(0x00ff),
+            NegotiateContextType::Unknown(0x00ff)
+        );
+        assert_eq!(u16::from(NegotiateContextType::Unknown(0x00ff)), 0x00ff);
+        let value = NegotiateContextValue::Unknown {
+            ctx_type: 0x00ff,
+            data: vec![],
+        };
+        assert_eq!(
+            value.get_matching_type(),
+            NegotiateContextType::Unknown(0x00ff)
+        );
     }
 }
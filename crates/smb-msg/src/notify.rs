@@ -31,6 +31,35 @@ pub struct ChangeNotifyRequest {
     reserved: u32,
 }
 
+impl ChangeNotifyRequest {
+    /// Default maximum size of the response, used by [`watch`][Self::watch]
+    /// and its shortcuts.
+    pub const DEFAULT_OUTPUT_BUFFER_LENGTH: u32 = 0x10000;
+
+    /// Creates a request watching for changes matching `filter` in the directory `file_id`,
+    /// and, if `watch_tree` is set, in all of its subdirectories.
+    pub fn watch(file_id: FileId, filter: NotifyFilter, watch_tree: bool) -> Self {
+        Self {
+            flags: NotifyFlags::new().with_watch_tree(watch_tree),
+            output_buffer_length: Self::DEFAULT_OUTPUT_BUFFER_LENGTH,
+            file_id,
+            completion_filter: filter,
+        }
+    }
+
+    /// Creates a request watching for any change in the directory tree of `file_id`,
+    /// including all of its subdirectories.
+    pub fn watch_tree(file_id: FileId) -> Self {
+        Self::watch(file_id, NotifyFilter::all(), true)
+    }
+
+    /// Creates a request watching for any change in the directory `file_id`,
+    /// without recursing into subdirectories.
+    pub fn watch_dir(file_id: FileId) -> Self {
+        Self::watch(file_id, NotifyFilter::all(), false)
+    }
+}
+
 /// Flags for SMB2 CHANGE_NOTIFY Request indicating how the operation must be processed.
 ///
 /// Reference: MS-SMB2 2.2.35
@@ -97,6 +126,16 @@ impl NotifyFilter {
             .with_stream_size(true)
             .with_stream_write(true)
     }
+
+    /// Returns the names of the filters that are set, in bit order.
+    pub fn iter_set(&self) -> impl Iterator<Item = &'static str> {
+        self.set_flags().into_iter()
+    }
+
+    /// Returns the names of the filters that are set, in bit order.
+    pub fn active(&self) -> Vec<&'static str> {
+        self.set_flags()
+    }
 }
 
 /// SMB2 CHANGE_NOTIFY Response packet sent by the server to transmit the
@@ -132,6 +171,7 @@ pub struct ServerToClientNotification {
     structure_size: u16,
     reserved: u16,
     /// Valid SMB_NOTIFICATION_ID enumeration notification type value.
+    #[br(temp)]
     #[bw(calc = notification.get_type())]
     notification_type: NotificationType,
     /// Corresponding structure type based on the notification type.
@@ -141,18 +181,43 @@ pub struct ServerToClientNotification {
 
 /// SMB_NOTIFICATION_ID enumeration values for server to client notifications.
 ///
+/// Notification types that are not defined here are parsed as [`NotificationType::Unknown`].
+///
 /// Reference: MS-SMB2 2.2.44.1
-#[smb_response_binrw]
-#[derive(Clone, Copy)]
-#[brw(repr(u32))]
+#[binrw::binrw]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[br(map = |x: u32| Self::from(x))]
+#[bw(map = |x: &Self| u32::from(*x))]
 pub enum NotificationType {
     /// Indicates the notification structure is SMB2_NOTIFY_SESSION_CLOSED.
-    NotifySessionClosed = 0,
+    NotifySessionClosed,
+    /// A notification type that is not defined in this enum.
+    Unknown(u32),
+}
+
+impl From<u32> for NotificationType {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => NotificationType::NotifySessionClosed,
+            _ => NotificationType::Unknown(value),
+        }
+    }
+}
+
+impl From<NotificationType> for u32 {
+    fn from(value: NotificationType) -> Self {
+        match value {
+            NotificationType::NotifySessionClosed => 0,
+            NotificationType::Unknown(value) => value,
+        }
+    }
 }
 
 /// Notification structure containing the specific notification data
 /// based on the notification type.
 ///
+/// Notifications of an unknown type are kept as raw bytes in [`Notification::Unknown`].
+///
 /// Reference: MS-SMB2 2.2.44.1
 #[smb_response_binrw]
 #[br(import(notification_type: NotificationType))]
@@ -160,12 +225,26 @@ pub enum Notification {
     /// Session closed notification structure.
     #[br(pre_assert(notification_type == NotificationType::NotifySessionClosed))]
     NotifySessionClosed(NotifySessionClosed),
+    /// A notification of a type that is not supported by this crate.
+    #[br(pre_assert(matches!(notification_type, NotificationType::Unknown(_))))]
+    Unknown {
+        /// The raw notification type identifier.
+        #[br(calc = u32::from(notification_type))]
+        #[bw(ignore)]
+        notification_type: u32,
+        /// The raw notification data.
+        #[br(parse_with = binrw::helpers::until_eof)]
+        data: Vec<u8>,
+    },
 }
 
 impl Notification {
     pub fn get_type(&self) -> NotificationType {
         match self {
             Notification::NotifySessionClosed(_) => NotificationType::NotifySessionClosed,
+            Notification::Unknown {
+                notification_type, ..
+            } => NotificationType::Unknown(*notification_type),
         }
     }
 }
@@ -203,6 +282,85 @@ mod tests {
         } => "2000000000080000d10500000c000000190000000c0000001700000000000000"
     }
 
+    #[test]
+    fn test_change_notify_watch_tree() {
+        let file_id: FileId = "000005d1-000c-0000-1900-00000c000000"
+            .parse::<Guid>()
+            .unwrap()
+            .into();
+        let request = ChangeNotifyRequest::watch_tree(file_id);
+        assert!(request.flags.watch_tree());
+        assert_eq!(request.completion_filter, NotifyFilter::all());
+        assert_eq!(
+            u32::from_le_bytes(request.completion_filter.into_bytes()),
+            0xfff
+        );
+        assert_eq!(request.output_buffer_length, 65536);
+        assert_eq!(request.file_id, file_id);
+
+        let request = ChangeNotifyRequest::watch_dir(file_id);
+        assert!(!request.flags.watch_tree());
+        assert_eq!(request.completion_filter, NotifyFilter::all());
+
+        let filter = NotifyFilter::new().with_file_name(true).with_size(true);
+        let request = ChangeNotifyRequest::watch(file_id, filter, true);
+        assert!(request.flags.watch_tree());
+        assert_eq!(request.completion_filter, filter);
+        assert_eq!(request.output_buffer_length, 65536);
+    }
+
+    #[test]
+    fn test_notify_filter_iter_set() {
+        let filter = NotifyFilter::new()
+            .with_file_name(true)
+            .with_last_write(true)
+            .with_stream_write(true);
+        assert_eq!(
+            filter.iter_set().collect::<Vec<_>>(),
+            vec!["file_name", "last_write", "stream_write"]
+        );
+        assert_eq!(NotifyFilter::new().iter_set().count(), 0);
+        assert_eq!(NotifyFilter::all().iter_set().count(), 12);
+    }
+
+    #[test]
+    fn test_notify_filter_active() {
+        assert_eq!(NotifyFilter::all().active(), NotifyFilter::FIELD_NAMES);
+        assert!(NotifyFilter::new().active().is_empty());
+    }
+
+    test_binrw_response! {
+        struct ServerToClientNotification => session_closed {
+            structure_size: 12,
+            notification: Notification::NotifySessionClosed(NotifySessionClosed {}),
+        } => "0c0000000000000000000000"
+    }
+
+    test_binrw_response! {
+        struct ServerToClientNotification => unknown {
+            structure_size: 12,
+            notification: Notification::Unknown {
+                notification_type: 5,
+                data: vec![0x01, 0x02, 0x03, 0x04],
+            },
+        } => "0c0000000500000001020304"
+    }
+
+    #[test]
+    fn test_notification_type_unknown() {
+        assert_eq!(
+            NotificationType::from(0),
+            NotificationType::NotifySessionClosed
+        );
+        assert_eq!(NotificationType::from(5), NotificationType::Unknown(5));
+        assert_eq!(u32::from(NotificationType::Unknown(5)), 5);
+        let notification = Notification::Unknown {
+            notification_type: 5,
+            data: vec![],
+        };
+        assert_eq!(notification.get_type(), NotificationType::Unknown(5));
+    }
+
     test_binrw_response! {
         struct ChangeNotifyResponse => pending {
             buffer: Default::default(),
@@ -61,7 +61,9 @@ pub struct LeaseBreakNotify {
 ///
 /// Reference: MS-SMB2 2.2.23.1
 #[smb_message_binrw]
+#[derive(Clone, Copy, smb_dtyp::ReprEnum)]
 #[brw(repr(u8))]
+#[repr(u8)]
 pub enum OplockLevel {
     /// No oplock is available.
     None = 0,
@@ -69,6 +71,8 @@ pub enum OplockLevel {
     II = 1,
     /// Exclusive oplock is available.
     Exclusive = 2,
+    /// A lease is requested, using a lease create context (MS-SMB2 2.2.13).
+    Lease = 0xff,
 }
 
 /// Lease state bitfield representing different types of caching permissions.
@@ -243,6 +243,23 @@ make_content!(
 );
 
 impl RequestContent {
+    /// The payload size covered by a single credit, when large MTU is supported.
+    ///
+    /// Reference: MS-SMB2 3.1.5.2
+    pub const CREDIT_PAYLOAD_SIZE: u32 = 65536;
+
+    /// Returns the credit charge of this request, when large MTU is supported.
+    ///
+    /// The charge is derived from the larger of [`req_payload_size`][Self::req_payload_size]
+    /// and [`expected_resp_size`][Self::expected_resp_size], and is at least 1.
+    ///
+    /// Reference: MS-SMB2 3.1.5.2
+    pub fn credit_charge(&self) -> u16 {
+        let payload_size = self.req_payload_size().max(self.expected_resp_size());
+        let charge = 1 + payload_size.saturating_sub(1) / Self::CREDIT_PAYLOAD_SIZE;
+        u16::try_from(charge).unwrap_or(u16::MAX)
+    }
+
     /// If this is a request has a payload, it returns the size of it.
     /// Otherwise, it returns 0.
     ///
@@ -275,6 +292,25 @@ impl RequestContent {
     }
 }
 
+/// Checks whether the given message content starts with the expected structure size.
+///
+/// `bytes` should point to the content of the message, directly after the SMB2 [`Header`].
+///
+/// Every SMB2 request and response (MS-SMB2 2.2.3 - 2.2.40) begins with a 2-byte StructureSize field.
+/// For structures declared using the `smb_request`, `smb_response` and `smb_request_response`
+/// attributes, the field is injected automatically, and validated on read. Enum messages, such as
+/// [`RequestContent`] and [`ResponseContent`], begin with the size field of the wrapped variant,
+/// so the expected size depends on the variant (e.g. [`OplockBreakAck`] vs [`LeaseBreakAck`]).
+///
+/// The transform headers ([`EncryptedMessage`] and [`CompressedMessage`])
+/// and the SMB2 [`Header`] itself do not begin with a structure size field.
+pub fn validate_structure_size(bytes: &[u8], expected: u16) -> bool {
+    match bytes {
+        [low, high, ..] => u16::from_le_bytes([*low, *high]) == expected,
+        _ => false,
+    }
+}
+
 macro_rules! make_plain {
     ($suffix:ident, $server_to_redir:literal, $binrw_attr:ident) => {
         pastey::paste! {
@@ -300,7 +336,7 @@ macro_rules! make_plain {
                     // default is a sync command, so `tree_id` must be set, and `HeaderFlags::async_command` is false
                     header: Header {
                         credit_charge: 0,
-                        status: Status::Success as u32,
+                        status: Status::U32_SUCCESS,
                         command,
                         credit_request: 0,
                         flags: HeaderFlags::new(),
@@ -321,3 +357,327 @@ macro_rules! make_plain {
 
 make_plain!(Request, false, smb_request_binrw);
 make_plain!(Response, true, smb_response_binrw);
+
+/// A chain of compounded requests, sent together in a single transport message.
+///
+/// When written, each message but the last is padded to an 8-byte boundary,
+/// and its [`Header::next_command`] is set to the offset of the next message.
+///
+/// Reference: MS-SMB2 3.2.4.1.4
+#[cfg(feature = "client")]
+#[derive(Debug)]
+pub struct CompoundRequest {
+    /// The messages in the chain, in order.
+    pub messages: Vec<PlainRequest>,
+}
+
+#[cfg(feature = "client")]
+impl CompoundRequest {
+    /// Alignment of each message in the chain.
+    pub const ALIGNMENT: usize = COMPOUND_ALIGNMENT;
+
+    /// Creates a new compound request from the given messages.
+    ///
+    /// Setting [`HeaderFlags::related_operations`] on the messages is up to the caller.
+    pub fn new(messages: Vec<PlainRequest>) -> Self {
+        Self { messages }
+    }
+
+    /// Creates a new compound request of related operations from the given messages,
+    /// setting [`HeaderFlags::related_operations`] on all messages but the first.
+    pub fn new_related(mut messages: Vec<PlainRequest>) -> Self {
+        for (i, message) in messages.iter_mut().enumerate() {
+            message.header.flags.set_related_operations(i > 0);
+        }
+        Self { messages }
+    }
+
+    /// Returns the total on-wire size of the compound request,
+    /// including the padding between messages.
+    ///
+    /// Message sizes depend on their variable-length fields, so each message is written to measure it.
+    /// A message that cannot be written (e.g. when a variable-length field does not fit in its on-wire length field)
+    /// is measured up to that field; writing the compound request reports the actual error.
+    pub fn total_size(&self) -> usize {
+        let last = self.messages.len().saturating_sub(1);
+        self.messages
+            .iter()
+            .enumerate()
+            .map(|(i, message)| {
+                let mut cursor = binrw::io::Cursor::new(Vec::new());
+                // Sending such a message fails anyway, when the compound request is written.
+                let _ = message.write(&mut cursor);
+                let size = cursor.into_inner().len();
+                if i == last {
+                    size
+                } else {
+                    size.next_multiple_of(Self::ALIGNMENT)
+                }
+            })
+            .sum()
+    }
+
+    /// Returns the total credit charge of the compound request,
+    /// which is the sum of the [credit charge][RequestContent::credit_charge] of each message.
+    pub fn credit_charge(&self) -> u16 {
+        self.messages.iter().fold(0u16, |total, m| {
+            total.saturating_add(m.content.credit_charge())
+        })
+    }
+}
+
+#[cfg(feature = "client")]
+impl binrw::meta::WriteEndian for CompoundRequest {
+    const ENDIAN: binrw::meta::EndianKind = binrw::meta::EndianKind::Endian(binrw::Endian::Little);
+}
+
+#[cfg(feature = "client")]
+impl BinWrite for CompoundRequest {
+    type Args<'a> = ();
+
+    fn write_options<W: binrw::io::Write + binrw::io::Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        _args: Self::Args<'_>,
+    ) -> BinResult<()> {
+        for (i, message) in self.messages.iter().enumerate() {
+            // Each message is written separately, since offsets inside
+            // the message are relative to the start of its header.
+            let mut cursor = binrw::io::Cursor::new(Vec::new());
+            let mut header = message.header.clone();
+            header.write_options(&mut cursor, endian, ())?;
+            message
+                .content
+                .write_options(&mut cursor, endian, (&header.command,))?;
+
+            if i + 1 < self.messages.len() {
+                let padded_size = (cursor.get_ref().len()).next_multiple_of(Self::ALIGNMENT);
+                cursor.get_mut().resize(padded_size, 0);
+                header.next_command = padded_size as u32;
+                cursor.set_position(0);
+                header.write_options(&mut cursor, endian, ())?;
+            }
+            writer.write_all(cursor.get_ref())?;
+        }
+        Ok(())
+    }
+}
+
+const COMPOUND_ALIGNMENT: usize = 8;
+
+/// An iterator over the messages in a compound chain, as received from the transport.
+///
+/// Each item is the slice of a single message, starting at its [`Header`],
+/// found by following the [`Header::next_command`] offsets.
+/// The slices may then be parsed as [`PlainRequest`] or [`PlainResponse`].
+///
+/// Reference: MS-SMB2 3.3.5.2.7
+pub struct CompoundChain<'a> {
+    remaining: Option<&'a [u8]>,
+}
+
+impl<'a> CompoundChain<'a> {
+    /// Offset of the [`Header::next_command`] field in the header.
+    const NEXT_COMMAND_OFFSET: usize = 20;
+
+    /// Creates a new iterator over the compound chain in `bytes`.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            remaining: Some(bytes),
+        }
+    }
+}
+
+impl<'a> Iterator for CompoundChain<'a> {
+    type Item = crate::Result<&'a [u8]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.remaining.take()?;
+        if bytes.len() < Header::STRUCT_SIZE {
+            return Some(Err(crate::SmbMsgError::InvalidData(format!(
+                "Compound message too short for a header: {} bytes",
+                bytes.len()
+            ))));
+        }
+        let next_command = u32::from_le_bytes(
+            bytes[Self::NEXT_COMMAND_OFFSET..Self::NEXT_COMMAND_OFFSET + 4]
+                .try_into()
+                .unwrap(),
+        ) as usize;
+        if next_command == 0 {
+            return Some(Ok(bytes));
+        }
+        if next_command < Header::STRUCT_SIZE
+            || next_command >= bytes.len()
+            || !next_command.is_multiple_of(COMPOUND_ALIGNMENT)
+        {
+            return Some(Err(crate::SmbMsgError::InvalidData(format!(
+                "Invalid next command offset in compound message: {next_command}"
+            ))));
+        }
+        let (message, rest) = bytes.split_at(next_command);
+        self.remaining = Some(rest);
+        Some(Ok(message))
+    }
+}
+
+#[cfg(all(test, feature = "client"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_structure_size_requests() {
+        use binrw::io::Cursor;
+
+        let requests: Vec<(RequestContent, u16)> = vec![
+            (
+                FlushRequest {
+                    file_id: FileId::EMPTY,
+                }
+                .into(),
+                24,
+            ),
+            (
+                CloseRequest {
+                    file_id: FileId::EMPTY,
+                }
+                .into(),
+                24,
+            ),
+            (EchoRequest::default().into(), 4),
+            (CancelRequest::default().into(), 4),
+        ];
+
+        for (content, expected) in requests {
+            let mut cursor = Cursor::new(Vec::new());
+            PlainRequest::new(content).write(&mut cursor).unwrap();
+            let bytes = cursor.into_inner();
+            assert!(validate_structure_size(
+                &bytes[Header::STRUCT_SIZE..],
+                expected
+            ));
+            assert!(!validate_structure_size(
+                &bytes[Header::STRUCT_SIZE..],
+                expected + 1
+            ));
+        }
+
+        assert!(!validate_structure_size(&[0x04], 4));
+    }
+
+    /// A create request opening `file.txt` for reading.
+    fn open_file_txt() -> CreateRequest {
+        CreateRequest::builder("file.txt")
+            .desired_access(smb_fscc::FileAccessMask::new().with_generic_read(true))
+            .share_access(ShareAccessFlags::new().with_read(true))
+            .build()
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn test_compound_request_total_size() {
+        use binrw::io::Cursor;
+
+        let create = open_file_txt();
+        let close = CloseRequest {
+            file_id: FileId::FULL,
+        };
+        let compound = CompoundRequest::new(vec![
+            PlainRequest::new(create.into()),
+            PlainRequest::new(close.into()),
+        ]);
+
+        let mut cursor = Cursor::new(Vec::new());
+        compound.write(&mut cursor).unwrap();
+        let bytes = cursor.into_inner();
+        assert_eq!(compound.total_size(), bytes.len());
+
+        let mut reader = Cursor::new(&bytes);
+        let first = PlainRequest::read(&mut reader).unwrap();
+        let next_command = first.header.next_command as usize;
+        assert_eq!(next_command % CompoundRequest::ALIGNMENT, 0);
+        assert_eq!(bytes.len(), next_command + Header::STRUCT_SIZE + 24);
+
+        reader.set_position(next_command as u64);
+        let second = PlainRequest::read(&mut reader).unwrap();
+        assert_eq!(second.header.next_command, 0);
+        assert_eq!(second.content.to_close().unwrap().file_id, FileId::FULL);
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn test_compound_request_related_chain() {
+        use binrw::io::Cursor;
+
+        let compound = CompoundRequest::new_related(vec![
+            PlainRequest::new(
+                FlushRequest {
+                    file_id: FileId::FULL,
+                }
+                .into(),
+            ),
+            PlainRequest::new(EchoRequest::default().into()),
+            PlainRequest::new(
+                CloseRequest {
+                    file_id: FileId::FULL,
+                }
+                .into(),
+            ),
+        ]);
+
+        let mut cursor = Cursor::new(Vec::new());
+        compound.write(&mut cursor).unwrap();
+        let bytes = cursor.into_inner();
+
+        let messages = CompoundChain::new(&bytes)
+            .map(|m| PlainRequest::read(&mut Cursor::new(m.unwrap())).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages
+                .iter()
+                .map(|m| m.header.command)
+                .collect::<Vec<_>>(),
+            vec![Command::Flush, Command::Echo, Command::Close]
+        );
+        assert_eq!(
+            messages
+                .iter()
+                .map(|m| m.header.flags.related_operations())
+                .collect::<Vec<_>>(),
+            vec![false, true, true]
+        );
+        assert_eq!(messages[2].header.next_command, 0);
+    }
+
+    #[test]
+    fn test_compound_chain_invalid() {
+        let mut bytes = vec![0u8; Header::STRUCT_SIZE * 2];
+        bytes[20] = 0x44; // not 8-byte aligned
+        let mut chain = CompoundChain::new(&bytes);
+        assert!(chain.next().unwrap().is_err());
+        assert!(chain.next().is_none());
+
+        assert!(CompoundChain::new(&[0u8; 10]).next().unwrap().is_err());
+        assert_eq!(CompoundChain::new(&bytes[..64]).count(), 1);
+    }
+
+    #[test]
+    fn test_compound_request_credit_charge() {
+        let create = open_file_txt();
+        let read = ReadRequest {
+            flags: ReadFlags::new(),
+            length: 0x30000 + 1,
+            offset: 0,
+            file_id: FileId::FULL,
+            minimum_count: 0,
+        };
+        let compound = CompoundRequest::new(vec![
+            PlainRequest::new(create.into()),
+            PlainRequest::new(read.into()),
+        ]);
+        assert_eq!(compound.messages[0].content.credit_charge(), 1);
+        assert_eq!(compound.messages[1].content.credit_charge(), 4);
+        assert_eq!(compound.credit_charge(), 5);
+    }
+}
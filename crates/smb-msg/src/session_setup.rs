@@ -84,6 +84,17 @@ impl SessionSetupRequest {
             buffer,
         }
     }
+
+    /// Sets the session identifier of a previously established session, for reconnection.
+    ///
+    /// When a client reconnects after a transport failure, it sets this to the ID of the
+    /// session it had before, so the server can clean up the previous session and any
+    /// state associated with it, without waiting for it to time out.
+    /// This must not be set when binding a session to a new channel.
+    pub fn with_previous_session(mut self, previous_session_id: u64) -> Self {
+        self.previous_session_id = previous_session_id;
+        self
+    }
 }
 
 /// SMB2 SESSION_SETUP Response packet sent by the server in response to a SESSION_SETUP Request.
@@ -163,6 +174,29 @@ mod tests {
         } => const_format::concatcp!("190000010100000000000000580059000000000000000000", SETUP_REQUEST_DATA)
     }
 
+    test_request! {
+        reconnect: SessionSetup {
+            flags: SetupRequestFlags::new(),
+            security_mode: SessionSecurityMode::new().with_signing_enabled(true),
+            buffer: hex_to_u8_array! {SETUP_REQUEST_DATA},
+            previous_session_id: 0x1122334455667788,
+            capabilities: NegotiateCapabilities::new().with_dfs(true),
+        } => const_format::concatcp!("190000010100000000000000580059008877665544332211", SETUP_REQUEST_DATA)
+    }
+
+    #[test]
+    fn test_session_setup_with_previous_session() {
+        let request = SessionSetupRequest::new(
+            hex_to_u8_array! {SETUP_REQUEST_DATA},
+            SessionSecurityMode::new().with_signing_enabled(true),
+            SetupRequestFlags::new(),
+            NegotiateCapabilities::new().with_dfs(true),
+        );
+        assert_eq!(request.previous_session_id, 0);
+        let request = request.with_previous_session(0x1122334455667788);
+        assert_eq!(request.previous_session_id, 0x1122334455667788);
+    }
+
     const SETUP_RESPONSE_DATA: &'static str = "a181b03081ada0030a0101a10c060a2b06010401823702020aa281970481944e544c4d53535000020000000c000c003800000015c28ae2abf194bdb756daa9140001000000000050005000440000000a005d580000000f410056004900560056004d0002000c00410056004900560056004d0001000c00410056004900560056004d0004000c00410076006900760056006d0003000c00410076006900760056006d0007000800a876d878c569db0100000000";
     test_response! {
         SessionSetup {
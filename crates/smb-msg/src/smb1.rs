@@ -5,9 +5,10 @@
 #[cfg(feature = "server")]
 use binrw::io::TakeSeekExt;
 use binrw::prelude::*;
+use std::borrow::Cow;
 
 use smb_dtyp::binrw_util::prelude::*;
-use smb_msg_derive::smb_request_binrw;
+use smb_msg_derive::{smb_request_binrw, smb_response_binrw};
 
 /// A (very) minimal SMB1 negotiation message,
 ///
@@ -55,35 +56,38 @@ pub struct SMB1NegotiateMessage {
 impl SMB1NegotiateMessage {
     /// Check if SMB2 is supported in the dialects list.
     pub fn is_smb2_supported(&self) -> bool {
-        self.dialects
-            .iter()
-            .any(|d| d.name.to_string() == "SMB 2.002")
+        self.dialects().any(|d| d == "SMB 2.002")
+    }
+
+    /// Returns the names of the dialects in the message, in order.
+    pub fn dialects(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        self.dialects.iter().map(|d| d.name())
     }
 }
 
-impl Default for SMB1NegotiateMessage {
-    fn default() -> Self {
+impl SMB1NegotiateMessage {
+    /// The dialects announced by [`SMB1NegotiateMessage::default`].
+    pub const DEFAULT_DIALECTS: &'static [&'static str] = &["NT LM 0.12", "SMB 2.002", "SMB 2.???"];
+
+    /// Creates a negotiation message that announces the given dialects, in order.
+    pub fn with_dialects(dialects: &[&str]) -> Self {
         Self {
             status: 0,
             flags: 0x18,
             flags2: 0xc853,
             security_features: [0; 8],
             byte_count: PosMarker::default(),
-            dialects: vec![
-                Smb1Dialect {
-                    name: binrw::NullString::from("NT LM 0.12"),
-                },
-                Smb1Dialect {
-                    name: binrw::NullString::from("SMB 2.002"),
-                },
-                Smb1Dialect {
-                    name: binrw::NullString::from("SMB 2.???"),
-                },
-            ],
+            dialects: dialects.iter().map(|d| Smb1Dialect::new(d)).collect(),
         }
     }
 }
 
+impl Default for SMB1NegotiateMessage {
+    fn default() -> Self {
+        Self::with_dialects(Self::DEFAULT_DIALECTS)
+    }
+}
+
 /// SMB1 Dialect String
 #[derive(BinRead, BinWrite, Debug, PartialEq, Eq, Clone)]
 #[brw(magic(b"\x02"))]
@@ -91,6 +95,111 @@ pub struct Smb1Dialect {
     name: binrw::NullString,
 }
 
+impl Smb1Dialect {
+    /// Creates a new dialect string with the given name.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: binrw::NullString::from(name),
+        }
+    }
+
+    /// Returns the name of the dialect. Invalid UTF-8 is replaced lossily.
+    pub fn name(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.name)
+    }
+}
+
+/// A minimal SMB1 negotiation response, as sent by a server that does not support SMB2.
+///
+/// Only the header and the raw parameter words and data are parsed;
+/// use [`SMB1NegotiateResponse::dialect_index`] to get the dialect selected by the server.
+#[smb_response_binrw]
+#[brw(little)]
+#[brw(magic(b"\xffSMB"))]
+pub struct SMB1NegotiateResponse {
+    #[bw(calc = 0x72)]
+    #[br(assert(_command == 0x72))]
+    #[br(temp)]
+    _command: u8,
+    /// The status of the negotiation.
+    pub status: u32,
+    /// The SMB1 header flags.
+    pub flags: u8,
+    /// The SMB1 header extended flags.
+    pub flags2: u16,
+    /// The high 16 bits of the process ID.
+    pub pid_high: u16,
+    /// The message signature, or security features, depending on the connection state.
+    pub security_features: [u8; 8],
+    reserved: u16,
+    /// The tree identifier.
+    pub tid: u16,
+    /// The low 16 bits of the process ID.
+    pub pid_low: u16,
+    /// The user identifier.
+    pub uid: u16,
+    /// The multiplex identifier, matching the response to its request.
+    pub mid: u16,
+    #[bw(try_calc = words.len().try_into())]
+    #[br(temp)]
+    _word_count: u8,
+    /// The parameter words of the response. The first word is the selected dialect index.
+    #[br(count = _word_count)]
+    pub words: Vec<u16>,
+    #[bw(try_calc = data.len().try_into())]
+    #[br(temp)]
+    _byte_count: u16,
+    /// The data bytes of the response.
+    #[br(count = _byte_count)]
+    pub data: Vec<u8>,
+}
+
+impl SMB1NegotiateResponse {
+    /// Dialect index value indicating that none of the requested dialects is supported.
+    pub const NO_DIALECT_SUPPORTED: u16 = 0xffff;
+
+    /// Returns the index of the dialect selected by the server, in the request's dialects list.
+    ///
+    /// Returns `None` if the response contains no dialect index,
+    /// or if the server does not support any of the requested dialects.
+    pub fn dialect_index(&self) -> Option<usize> {
+        self.words
+            .first()
+            .filter(|&&index| index != Self::NO_DIALECT_SUPPORTED)
+            .map(|&index| index as usize)
+    }
+
+    /// Returns the name of the dialect selected by the server, from the `request` dialects list.
+    pub fn selected_dialect<'a>(&self, request: &'a SMB1NegotiateMessage) -> Option<Cow<'a, str>> {
+        request.dialects().nth(self.dialect_index()?)
+    }
+}
+
+/// A response to a multi-protocol negotiation request.
+///
+/// A server that supports SMB2 responds with an SMB2 negotiate response,
+/// while a server that only supports SMB1 responds with an SMB1 negotiate response.
+#[cfg(feature = "client")]
+#[derive(Debug)]
+pub enum MultiProtocolNegotiateResponse {
+    /// The server responded with SMB1, and does not support SMB2.
+    Smb1(SMB1NegotiateResponse),
+    /// The server responded with SMB2, and negotiation should continue over SMB2.
+    Smb2(crate::Response),
+}
+
+#[cfg(feature = "client")]
+impl TryFrom<&[u8]> for MultiProtocolNegotiateResponse {
+    type Error = binrw::Error;
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        if value.starts_with(b"\xffSMB") {
+            SMB1NegotiateResponse::read(&mut std::io::Cursor::new(value)).map(Self::Smb1)
+        } else {
+            crate::Response::try_from(value).map(Self::Smb2)
+        }
+    }
+}
+
 #[cfg(feature = "client")]
 impl TryInto<Vec<u8>> for SMB1NegotiateMessage {
     type Error = binrw::Error;
@@ -103,11 +212,94 @@ impl TryInto<Vec<u8>> for SMB1NegotiateMessage {
 
 #[cfg(test)]
 mod tests {
-    #[cfg(feature = "client")]
     use super::*;
+    use crate::*;
+
     #[cfg(feature = "client")]
     smb_tests::test_binrw_write! {
         SMB1NegotiateMessage: SMB1NegotiateMessage::default() =>
             "ff534d4272000000001853c8000000000000000000000000ffff010000000000002200024e54204c4d20302e31320002534d4220322e3030320002534d4220322e3f3f3f00"
     }
+
+    #[test]
+    fn test_default_dialect_names() {
+        let msg = SMB1NegotiateMessage::default();
+        assert_eq!(
+            msg.dialects().collect::<Vec<_>>(),
+            vec!["NT LM 0.12", "SMB 2.002", "SMB 2.???"]
+        );
+        assert!(msg.is_smb2_supported());
+    }
+
+    #[test]
+    fn test_with_dialects() {
+        let msg = SMB1NegotiateMessage::with_dialects(&["PC NETWORK PROGRAM 1.0", "SMB 2.002"]);
+        assert_eq!(
+            msg.dialects().collect::<Vec<_>>(),
+            vec!["PC NETWORK PROGRAM 1.0", "SMB 2.002"]
+        );
+        assert!(msg.is_smb2_supported());
+
+        let msg = SMB1NegotiateMessage::with_dialects(&["NT LM 0.12"]);
+        assert!(!msg.is_smb2_supported());
+        assert_eq!(msg.dialects.first().unwrap().name(), "NT LM 0.12");
+    }
+
+    const SMB1_NEGOTIATE_RESPONSE: &str =
+        "ff534d4272000000009853c800000000000000000000000000000100000000000100000000";
+
+    test_binrw_response! {
+        SMB1NegotiateResponse: SMB1NegotiateResponse {
+            status: 0,
+            flags: 0x98,
+            flags2: 0xc853,
+            pid_high: 0,
+            security_features: [0; 8],
+            tid: 0,
+            pid_low: 1,
+            uid: 0,
+            mid: 0,
+            words: vec![0],
+            data: vec![],
+        } => SMB1_NEGOTIATE_RESPONSE
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_smb1_negotiate_response() {
+        let data = smb_tests::hex_to_u8_array! {SMB1_NEGOTIATE_RESPONSE};
+        let MultiProtocolNegotiateResponse::Smb1(response) =
+            MultiProtocolNegotiateResponse::try_from(data.as_slice()).unwrap()
+        else {
+            panic!("Expected SMB1 response");
+        };
+        assert_eq!(response.status, 0);
+        assert_eq!(response.dialect_index(), Some(0));
+        assert_eq!(
+            response
+                .selected_dialect(&SMB1NegotiateMessage::default())
+                .unwrap(),
+            "NT LM 0.12"
+        );
+
+        let mut data = data;
+        // DialectIndex = 0xffff
+        data[33] = 0xff;
+        data[34] = 0xff;
+        let response = SMB1NegotiateResponse::read(&mut std::io::Cursor::new(&data)).unwrap();
+        assert_eq!(response.dialect_index(), None);
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_multi_protocol_response_smb2() {
+        let data = smb_tests::hex_to_u8_array! {
+            "fe534d4240000000000000000d0001000100000000000000050000000000000000000000
+            0000000000000000000000000000000000000000000000000000000004000000"
+        };
+        assert!(matches!(
+            MultiProtocolNegotiateResponse::try_from(data.as_slice()).unwrap(),
+            MultiProtocolNegotiateResponse::Smb2(crate::Response::Plain(_))
+        ));
+    }
 }
@@ -10,6 +10,19 @@ macro_rules! _test_generic_read {
         $req_or_resp:ident => $test_name:ident, $command:expr => $struct_name:ident {
             $($field_name:ident : $field_value:expr),* $(,)?
         } => $hex:expr
+    ) => {
+        pastey::paste! {
+            _test_generic_read! {
+                $req_or_resp => $test_name, $command => $struct_name = [<$struct_name $req_or_resp:camel>] {
+                    $(
+                        $field_name: $field_value,
+                    )*
+                } => $hex
+            }
+        }
+    };
+    (
+        $req_or_resp:ident => $test_name:ident, $command:expr => $struct_name:ident = $value:expr => $hex:expr
     ) => {
         pastey::paste! {
             #[test]
@@ -39,11 +52,7 @@ macro_rules! _test_generic_read {
 
                 let msg: [<Plain $req_or_resp:camel>] = cursor.read_le().unwrap();
                 let msg: [<$struct_name $req_or_resp:camel>] = msg.content.[<to_ $struct_name:lower>]().unwrap();
-                assert_eq!(msg, [<$struct_name $req_or_resp:camel>] {
-                    $(
-                        $field_name: $field_value,
-                    )*
-                });
+                assert_eq!(msg, $value);
             }
         }
     };
@@ -57,14 +66,23 @@ macro_rules! _test_generic_write {
         } => $hex:expr
     ) => {
         pastey::paste! {
-            #[test]
-            fn [<test_content_ $req_or_resp:lower _ $test_name:snake _write>]() {
-                use ::binrw::{io::Cursor, prelude::*};
-                let response = [<$struct_name $req_or_resp:camel>] {
+            _test_generic_write! {
+                $req_or_resp => $test_name, $command => $struct_name = [<$struct_name $req_or_resp:camel>] {
                     $(
                         $field_name: $field_value,
                     )*
-                };
+                } => $hex
+            }
+        }
+    };
+    (
+        $req_or_resp:ident => $test_name:ident, $command:expr => $struct_name:ident = $value:expr => $hex:expr
+    ) => {
+        pastey::paste! {
+            #[test]
+            fn [<test_content_ $req_or_resp:lower _ $test_name:snake _write>]() {
+                use ::binrw::{io::Cursor, prelude::*};
+                let response: [<$struct_name $req_or_resp:camel>] = $value;
                 let mut cursor = Cursor::new(Vec::new());
                 let mut msg = [<Plain $req_or_resp:camel>]::new_with_command(response.into(), $command);
 
@@ -106,6 +124,22 @@ macro_rules! _test_generic_impl {
             } => $hex
         }
     };
+    (
+        $impl_macro:ident, $req_or_resp:ident => $struct_name:ident = $value:expr => $hex:expr
+    ) => {
+        _test_generic_impl! {
+            $impl_macro, $req_or_resp =>
+            $struct_name: $struct_name = $value => $hex
+        }
+    };
+    (
+        $impl_macro:ident, $req_or_resp:ident => $test_name:ident: $struct_name:ident = $value:expr => $hex:expr
+    ) => {
+        _test_generic_impl! {
+            $impl_macro, $req_or_resp =>
+            $test_name, Command::$struct_name => $struct_name = $value => $hex
+        }
+    };
     (
         $impl_macro:ident, $($v:tt)+
     ) => {
@@ -221,3 +255,74 @@ pub(crate) use test_response;
 pub(crate) use test_response_read;
 #[allow(unused_imports)]
 pub(crate) use test_response_write;
+
+/// Checks that all the variants of a [`ReprEnum`][smb_dtyp::ReprEnum] round-trip through
+/// their raw values, and that a sample of unknown raw values fail to convert cleanly.
+pub fn check_repr_enum<E>()
+where
+    E: smb_dtyp::ReprEnum
+        + TryFrom<E::Repr, Error = smb_dtyp::UnknownEnumValue<E::Repr>>
+        + std::fmt::Debug
+        + PartialEq,
+    E::Repr: TryFrom<u64> + Into<u64> + PartialEq + std::fmt::Debug,
+{
+    assert!(!E::VARIANTS.is_empty());
+    for variant in E::VARIANTS {
+        let raw = variant.to_raw();
+        assert_eq!(E::from_raw(raw).as_ref(), Some(variant));
+        assert_eq!(E::try_from(raw).as_ref().ok(), Some(variant));
+    }
+
+    let known: Vec<u64> = E::VARIANTS.iter().map(|v| v.to_raw().into()).collect();
+    let samples = (0..=0x200)
+        .chain(known.iter().flat_map(|&v| [v.wrapping_sub(1), v + 1]))
+        .chain([0xff, 0xffff, 0xc000_0000, 0xffff_ffff]);
+    for sample in samples.filter(|s| !known.contains(s)) {
+        let Ok(raw) = E::Repr::try_from(sample) else {
+            continue;
+        };
+        assert!(E::from_raw(raw).is_none());
+        assert_eq!(E::try_from(raw).map_err(|e| e.value), Err(raw));
+    }
+}
+
+/// Generates a test calling [`check_repr_enum`] for each of the given enums.
+macro_rules! test_repr_enums {
+    ($($enum_type:ident),+ $(,)?) => {
+        $(
+            pastey::paste! {
+                #[test]
+                fn [<test_repr_enum_ $enum_type:snake>]() {
+                    $crate::test::check_repr_enum::<$enum_type>();
+                }
+            }
+        )+
+    };
+}
+
+mod repr_enums {
+    use crate::*;
+
+    test_repr_enums!(
+        Command,
+        Dialect,
+        NegotiateDialect,
+        HashAlgorithm,
+        EncryptionCipher,
+        CompressionAlgorithm,
+        RdmaTransformId,
+        SigningAlgorithmId,
+        OplockLevel,
+        ShareType,
+        ImpersonationLevel,
+        CreateDisposition,
+        CreateAction,
+        CommunicationChannel,
+        ReferralLevel,
+        DfsServerType,
+        ErrorId,
+        SrvHashRetrievalType,
+        FsctlCodes,
+        InfoType,
+    );
+}
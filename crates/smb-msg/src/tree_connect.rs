@@ -1,12 +1,17 @@
 //! Tree (share) connect & disconnect messages
 
+#[cfg(feature = "server")]
+use binrw::io::SeekFrom;
 use binrw::prelude::*;
 use binrw::{NullWideString, io::TakeSeekExt};
 use modular_bitfield::prelude::*;
 use smb_dtyp::{
     binrw_util::prelude::*,
-    security::{ACL, ClaimSecurityAttributeRelativeV1, SID},
+    security::{ACL, AclRevision, ClaimSecurityAttributeRelativeV1, SID},
 };
+use smb_fscc::FileAccessMask;
+
+use crate::{ErrorId, ErrorResponse, ShareRedirectErrorContext, Status};
 
 /// Flags for SMB2 TREE_CONNECT Request
 ///
@@ -70,11 +75,11 @@ pub struct TreeConnectRequest {
 
     // -- Extension --
     #[br(if(flags.extension_present()))]
-    #[br(seek_before = tree_connect_context_offset.unwrap().seek_relative(true))]
+    #[br(seek_before = SeekFrom::Start(tree_connect_context_offset.as_ref().unwrap().value.into()))]
     #[br(count = tree_connect_context_count.unwrap_or(0))]
     #[bw(if(!tree_connect_contexts.is_empty()))]
     #[bw(write_with = PosMarker::write_aoff_m, args(tree_connect_context_offset.as_ref()))]
-    tree_connect_contexts: Vec<TreeConnectContext>,
+    pub tree_connect_contexts: Vec<TreeConnectContext>,
 }
 
 /// SMB2 TREE_CONNECT_CONTEXT Request structure
@@ -87,21 +92,33 @@ pub struct TreeConnectContext {
     /// Type of context in the Data field
     #[bw(calc = 1)]
     #[br(assert(context_type == 1))]
+    #[br(temp)]
     context_type: u16,
     /// Length in bytes of the Data field
-    data_length: u16,
+    #[bw(calc = PosMarker::default())]
+    #[br(temp)]
+    data_length: PosMarker<u16>,
     reserved: u32,
-    data: RemotedIdentityTreeConnect,
+    /// The remoted identity carried by this context
+    #[bw(write_with = PosMarker::write_size, args(&data_length))]
+    pub data: RemotedIdentityTreeConnect,
+}
+
+impl From<RemotedIdentityTreeConnect> for TreeConnectContext {
+    fn from(data: RemotedIdentityTreeConnect) -> Self {
+        TreeConnectContext { data }
+    }
 }
 
 macro_rules! make_remoted_identity_connect{
     (
-        $($field:ident: $value:ty),*
+        $($field:ident: $value:ty),*;
+        $last_field:ident: $last_value:ty
     ) => {
         pastey::paste! {
 
-#[binwrite]
-#[derive(Debug, BinRead, PartialEq, Eq)]
+#[binrw::binrw]
+#[derive(Debug, PartialEq, Eq)]
 /// SMB2_REMOTED_IDENTITY_TREE_CONNECT Context
 ///
 /// Contains remoted identity tree connect context data with user information,
@@ -109,11 +126,18 @@ macro_rules! make_remoted_identity_connect{
 ///
 /// Reference: MS-SMB2 2.2.9.2.1
 pub struct RemotedIdentityTreeConnect {
-    #[bw(calc = PosMarker::new(1))]
-    #[br(assert(_ticket_type.value == 1))]
-    _ticket_type: PosMarker<u16>,
+    // Offsets are relative to the start of this structure.
+    #[bw(calc = PosMarker::default())]
+    #[br(temp)]
+    _start: PosMarker<()>,
+    #[bw(calc = 1)]
+    #[br(assert(_ticket_type == 1))]
+    #[br(temp)]
+    _ticket_type: u16,
     /// Total size of this structure
-    ticket_size: u16,
+    #[bw(calc = PosMarker::default())]
+    #[br(temp)]
+    ticket_size: PosMarker<u16>,
 
     // Offsets
     $(
@@ -121,13 +145,19 @@ pub struct RemotedIdentityTreeConnect {
         #[br(temp)]
         [<_$field _offset>]: PosMarker<u16>,
     )*
+    #[bw(calc = PosMarker::default())]
+    #[br(temp)]
+    [<_$last_field _offset>]: PosMarker<u16>,
 
     // Values
     $(
-        #[br(seek_before = _ticket_type.seek_from([<_$field _offset>].value as u64))]
-        #[bw(write_with = PosMarker::write_roff_b, args(&[<_$field _offset>], &_ticket_type))]
-        $field: $value,
+        #[br(seek_before = _start.seek_from([<_$field _offset>].value as u64))]
+        #[bw(write_with = PosMarker::write_roff_b, args(&[<_$field _offset>], &_start))]
+        pub $field: $value,
     )*
+    #[br(seek_before = _start.seek_from([<_$last_field _offset>].value as u64))]
+    #[bw(write_with = PosMarker::write_roff_b_total, args(&[<_$last_field _offset>], &_start, &ticket_size))]
+    pub $last_field: $last_value,
 }
         }
     }
@@ -144,10 +174,172 @@ make_remoted_identity_connect! {
     owner: BlobData<SID>,
     default_dacl: BlobData<ACL>,
     device_groups: SidArrayData,
-    user_claims: BlobData<ClaimSecurityAttributeRelativeV1>,
+    user_claims: BlobData<ClaimSecurityAttributeRelativeV1>;
+    // Last, so writing it also fills the ticket size.
     device_claims: BlobData<ClaimSecurityAttributeRelativeV1>
 }
 
+/// A builder for [`RemotedIdentityTreeConnect`].
+///
+/// Only the user is required; all the other fields default to empty values,
+/// the owner defaults to the user SID, and the default DACL to an empty ACL.
+/// The ticket size and all the offsets are calculated when the ticket is written.
+///
+/// ```
+/// use smb_dtyp::SID;
+/// use smb_msg::{RemotedIdentityTreeConnectBuilder, SidAttrSeGroup, TreeConnectRequest};
+/// let user: SID = "S-1-5-21-782712087-4182988437-2163400469-1002".parse().unwrap();
+/// let identity = RemotedIdentityTreeConnectBuilder::new(user, SidAttrSeGroup::new())
+///     .user_name("user")
+///     .domain("DOMAIN")
+///     .build();
+/// let request = TreeConnectRequest::with_remoted_identity(r"\\server\share", identity);
+/// ```
+#[derive(Debug)]
+pub struct RemotedIdentityTreeConnectBuilder {
+    user: SidAttrData,
+    user_name: String,
+    domain: String,
+    groups: Vec<SidAttrData>,
+    restricted_groups: Vec<SidAttrData>,
+    privileges: Vec<LuidAttrData>,
+    primary_group: Vec<SidAttrData>,
+    owner: Option<SID>,
+    default_dacl: Option<ACL>,
+    device_groups: Vec<SidAttrData>,
+    user_claims: ClaimSecurityAttributeRelativeV1,
+    device_claims: ClaimSecurityAttributeRelativeV1,
+}
+
+impl RemotedIdentityTreeConnectBuilder {
+    /// Creates a new builder for the specified user SID and its attributes.
+    pub fn new(user: SID, attr: SidAttrSeGroup) -> Self {
+        Self {
+            user: SidAttrData {
+                sid_data: user,
+                attr,
+            },
+            user_name: String::new(),
+            domain: String::new(),
+            groups: vec![],
+            restricted_groups: vec![],
+            privileges: vec![],
+            primary_group: vec![],
+            owner: None,
+            default_dacl: None,
+            device_groups: vec![],
+            user_claims: Default::default(),
+            device_claims: Default::default(),
+        }
+    }
+
+    /// Sets the user name.
+    pub fn user_name(mut self, user_name: &str) -> Self {
+        self.user_name = user_name.to_string();
+        self
+    }
+
+    /// Sets the domain name of the user.
+    pub fn domain(mut self, domain: &str) -> Self {
+        self.domain = domain.to_string();
+        self
+    }
+
+    /// Sets the groups the user is a member of.
+    pub fn groups(mut self, groups: Vec<SidAttrData>) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    /// Sets the restricted groups of the user.
+    pub fn restricted_groups(mut self, restricted_groups: Vec<SidAttrData>) -> Self {
+        self.restricted_groups = restricted_groups;
+        self
+    }
+
+    /// Sets the privileges of the user.
+    pub fn privileges(mut self, privileges: Vec<LuidAttrData>) -> Self {
+        self.privileges = privileges;
+        self
+    }
+
+    /// Sets the primary group of the user.
+    pub fn primary_group(mut self, primary_group: Vec<SidAttrData>) -> Self {
+        self.primary_group = primary_group;
+        self
+    }
+
+    /// Sets the owner SID for objects created by the user. Defaults to the user SID.
+    pub fn owner(mut self, owner: SID) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    /// Sets the default DACL for objects created by the user. Defaults to an empty ACL.
+    pub fn default_dacl(mut self, default_dacl: ACL) -> Self {
+        self.default_dacl = Some(default_dacl);
+        self
+    }
+
+    /// Sets the device groups.
+    pub fn device_groups(mut self, device_groups: Vec<SidAttrData>) -> Self {
+        self.device_groups = device_groups;
+        self
+    }
+
+    /// Sets the user claims.
+    pub fn user_claims(mut self, user_claims: ClaimSecurityAttributeRelativeV1) -> Self {
+        self.user_claims = user_claims;
+        self
+    }
+
+    /// Sets the device claims.
+    pub fn device_claims(mut self, device_claims: ClaimSecurityAttributeRelativeV1) -> Self {
+        self.device_claims = device_claims;
+        self
+    }
+
+    pub fn build(self) -> RemotedIdentityTreeConnect {
+        let owner = self.owner.unwrap_or_else(|| self.user.sid_data.clone());
+        let default_dacl = self.default_dacl.unwrap_or(ACL {
+            acl_revision: AclRevision::Nt4,
+            ace: vec![],
+        });
+        RemotedIdentityTreeConnect {
+            user: self.user,
+            user_name: self.user_name.as_str().into(),
+            domain: self.domain.as_str().into(),
+            groups: ArrayData { list: self.groups },
+            restricted_groups: ArrayData {
+                list: self.restricted_groups,
+            },
+            privileges: ArrayData {
+                list: self
+                    .privileges
+                    .into_iter()
+                    .map(|blob_data| BlobData { blob_data })
+                    .collect(),
+            },
+            primary_group: ArrayData {
+                list: self.primary_group,
+            },
+            owner: BlobData { blob_data: owner },
+            default_dacl: BlobData {
+                blob_data: default_dacl,
+            },
+            device_groups: ArrayData {
+                list: self.device_groups,
+            },
+            user_claims: BlobData {
+                blob_data: self.user_claims,
+            },
+            device_claims: BlobData {
+                blob_data: self.device_claims,
+            },
+        }
+    }
+}
+
 /// BLOB_DATA structure containing variable-length binary data
 ///
 /// Reference: MS-SMB2 2.2.9.2.1.1
@@ -155,13 +347,15 @@ make_remoted_identity_connect! {
 #[derive(Debug, PartialEq, Eq)]
 pub struct BlobData<T>
 where
-    T: BinRead + BinWrite,
+    T: BinRead + for<'b> BinWrite<Args<'b> = ()>,
     for<'a> <T as BinRead>::Args<'a>: Default,
-    for<'b> <T as BinWrite>::Args<'b>: Default,
 {
     /// Size of the blob data
+    #[bw(calc = PosMarker::default())]
+    #[br(temp)]
     blob_size: PosMarker<u16>,
     #[br(map_stream = |s| s.take_seek(blob_size.value as u64))]
+    #[bw(write_with = PosMarker::write_size, args(&blob_size))]
     pub blob_data: T,
 }
 
@@ -260,6 +454,32 @@ impl TreeConnectRequest {
             tree_connect_contexts: vec![],
         }
     }
+
+    /// Creates a new tree connect request, with a remoted identity tree connect context.
+    ///
+    /// The [`TreeConnectRequestFlags::extension_present`] flag is set accordingly.
+    pub fn with_remoted_identity(
+        name: &str,
+        identity: RemotedIdentityTreeConnect,
+    ) -> TreeConnectRequest {
+        TreeConnectRequest {
+            flags: TreeConnectRequestFlags::new().with_extension_present(true),
+            buffer: name.into(),
+            tree_connect_contexts: vec![identity.into()],
+        }
+    }
+
+    /// Sets the [`TreeConnectRequestFlags::redirect_to_owner`] flag,
+    /// indicating that the client can handle synchronous share redirects.
+    ///
+    /// A scale-out server may then fail the tree connect with `STATUS_BAD_NETWORK_NAME`,
+    /// and an error context of type [`ErrorId::ShareRedirect`]
+    /// pointing to the node that owns the share. The client is expected to connect to that
+    /// node and retry there. This is only valid for the SMB 3.1.1 dialect.
+    pub fn with_redirect_to_owner(mut self) -> Self {
+        self.flags.set_redirect_to_owner(true);
+        self
+    }
 }
 
 /// SMB2 TREE_CONNECT Response
@@ -277,7 +497,86 @@ pub struct TreeConnectResponse {
     /// Capabilities for this share
     pub capabilities: TreeCapabilities,
     /// Maximal access for the user that establishes the tree connect on the share
-    pub maximal_access: u32,
+    ///
+    /// This used to be a raw `u32`; [`FileAccessMask`] converts from and into one
+    /// (e.g. `FileAccessMask::from(0x001f01ff)`).
+    pub maximal_access: FileAccessMask,
+}
+
+impl TreeConnectResponse {
+    /// Whether the share is a named pipe share (e.g. `IPC$`).
+    pub fn is_pipe(&self) -> bool {
+        self.share_type.is_pipe()
+    }
+
+    /// Whether the share is a physical disk share.
+    pub fn is_disk(&self) -> bool {
+        self.share_type.is_disk()
+    }
+
+    /// Whether the share is a printer share.
+    pub fn is_print(&self) -> bool {
+        self.share_type.is_print()
+    }
+
+    /// Whether the share is present in a DFS tree structure, as indicated by the share capabilities.
+    ///
+    /// See [`is_dfs_share`][Self::is_dfs_share] for the matching share flag.
+    pub fn supports_dfs(&self) -> bool {
+        self.capabilities.dfs()
+    }
+
+    /// Whether the share is present in a DFS tree structure, as indicated by the share flags.
+    ///
+    /// See [`supports_dfs`][Self::supports_dfs] for the matching share capability.
+    pub fn is_dfs_share(&self) -> bool {
+        self.share_flags.dfs()
+    }
+
+    /// Whether the server requires encryption of remote file access messages on this share.
+    pub fn requires_encryption(&self) -> bool {
+        self.share_flags.encrypt_data()
+    }
+}
+
+/// The reason a tree connect failed, as classified from the response status.
+///
+/// Use [`classify_tree_connect_error`] to classify a status, or
+/// [`TreeConnectError::from_error_response`] to also detect share redirects.
+#[derive(Debug, PartialEq, Eq)]
+pub enum TreeConnectError {
+    /// The share does not exist on the server (`STATUS_BAD_NETWORK_NAME`).
+    BadNetworkName,
+    /// The user is not allowed to access the share
+    /// (`STATUS_ACCESS_DENIED` or `STATUS_NETWORK_ACCESS_DENIED`).
+    AccessDenied,
+    /// The share is hosted on another server, and the client should connect to it instead.
+    Redirect(ShareRedirectErrorContext),
+    /// Any other failure.
+    Other(Status),
+}
+
+impl TreeConnectError {
+    /// Classifies a failed tree connect from its status and error response.
+    ///
+    /// Servers that redirect a share fail the tree connect with `STATUS_BAD_NETWORK_NAME`,
+    /// along with a share redirect error context, which takes precedence over the status.
+    pub fn from_error_response(status: Status, error: &ErrorResponse) -> Self {
+        error
+            .find_context(ErrorId::ShareRedirect)
+            .and_then(|context| context.as_share_redirect().ok())
+            .map(TreeConnectError::Redirect)
+            .unwrap_or_else(|| classify_tree_connect_error(status))
+    }
+}
+
+/// Classifies the status of a failed tree connect. See [`TreeConnectError`].
+pub fn classify_tree_connect_error(status: Status) -> TreeConnectError {
+    match status {
+        Status::BadNetworkName => TreeConnectError::BadNetworkName,
+        Status::AccessDenied | Status::NetworkAccessDenied => TreeConnectError::AccessDenied,
+        _ => TreeConnectError::Other(status),
+    }
 }
 
 /// Share caching mode for offline file access
@@ -295,6 +594,19 @@ pub enum ShareCacheMode {
     All = 0xf,
 }
 
+impl std::fmt::Display for ShareCacheMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode_as_string = match self {
+            ShareCacheMode::Manual => "Manual",
+            ShareCacheMode::Auto => "Auto",
+            ShareCacheMode::Vdo => "VDO",
+            ShareCacheMode::NoCache => "NoCache",
+            ShareCacheMode::All => "All",
+        };
+        write!(f, "{} ({:#x})", mode_as_string, *self as u8)
+    }
+}
+
 /// Share flags indicating various share properties
 ///
 /// Reference: MS-SMB2 2.2.10
@@ -368,8 +680,9 @@ pub struct TreeCapabilities {
 ///
 /// Reference: MS-SMB2 2.2.10
 #[smb_response_binrw]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, smb_dtyp::ReprEnum)]
 #[brw(repr(u8))]
+#[repr(u8)]
 pub enum ShareType {
     /// Physical disk share
     Disk = 0x1,
@@ -379,6 +692,23 @@ pub enum ShareType {
     Print = 0x3,
 }
 
+impl ShareType {
+    /// Whether this is a named pipe share (e.g. `IPC$`).
+    pub fn is_pipe(&self) -> bool {
+        matches!(self, ShareType::Pipe)
+    }
+
+    /// Whether this is a physical disk share.
+    pub fn is_disk(&self) -> bool {
+        matches!(self, ShareType::Disk)
+    }
+
+    /// Whether this is a printer share.
+    pub fn is_print(&self) -> bool {
+        matches!(self, ShareType::Print)
+    }
+}
+
 /// SMB2 TREE_DISCONNECT Request
 ///
 /// Sent by a client to request that the tree connect that is specified in the TreeId within
@@ -408,7 +738,52 @@ mod tests {
 
     use super::*;
 
-    // TODO(test): Add tests with tree connect contexts.
+    #[test]
+    fn test_classify_tree_connect_error() {
+        assert_eq!(
+            classify_tree_connect_error(Status::BadNetworkName),
+            TreeConnectError::BadNetworkName
+        );
+        assert_eq!(
+            classify_tree_connect_error(Status::AccessDenied),
+            TreeConnectError::AccessDenied
+        );
+        assert_eq!(
+            classify_tree_connect_error(Status::NetworkAccessDenied),
+            TreeConnectError::AccessDenied
+        );
+        assert_eq!(
+            classify_tree_connect_error(Status::NetworkNameDeleted),
+            TreeConnectError::Other(Status::NetworkNameDeleted)
+        );
+    }
+
+    #[test]
+    fn test_tree_connect_error_redirect() {
+        let redirect = ShareRedirectErrorContext {
+            ip_addr_move_list: vec![MoveDstIpAddr::V4("10.0.0.2".parse().unwrap())],
+            resource_name: "share".into(),
+        };
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        redirect.write_le(&mut cursor).unwrap();
+        let error = ErrorResponse {
+            error_data: vec![ErrorResponseContext {
+                error_id: ErrorId::ShareRedirect,
+                error_data: cursor.into_inner(),
+            }],
+        };
+        assert_eq!(
+            TreeConnectError::from_error_response(Status::BadNetworkName, &error),
+            TreeConnectError::Redirect(redirect)
+        );
+
+        let error = ErrorResponse { error_data: vec![] };
+        assert_eq!(
+            TreeConnectError::from_error_response(Status::BadNetworkName, &error),
+            TreeConnectError::BadNetworkName
+        );
+    }
+
     test_request! {
         TreeConnect {
             flags: TreeConnectRequestFlags::new(),
@@ -417,12 +792,242 @@ mod tests {
         } => "0900000048002a005c005c006100640063002e0061007600690076002e006c006f00630061006c005c004900500043002400"
     }
 
+    test_request! {
+        redirect_to_owner: TreeConnect {
+            flags: TreeConnectRequestFlags::new().with_redirect_to_owner(true),
+            buffer: r"\\adc.aviv.local\IPC$".into(),
+            tree_connect_contexts: vec![],
+        } => "0900020048002a005c005c006100640063002e0061007600690076002e006c006f00630061006c005c004900500043002400"
+    }
+
+    #[test]
+    fn test_tree_connect_with_redirect_to_owner() {
+        let request = TreeConnectRequest::new(r"\\adc.aviv.local\IPC$").with_redirect_to_owner();
+        assert!(request.flags.redirect_to_owner());
+        assert!(!request.flags.extension_present());
+        assert_eq!(u16::from_le_bytes(request.flags.into_bytes()), 0x0002);
+    }
+
     test_binrw_response! {
         struct TreeConnectResponse {
             share_type: ShareType::Disk,
             share_flags: ShareFlags::new().with_access_based_directory_enum(true),
             capabilities: TreeCapabilities::new(),
-            maximal_access: 0x001f01ff,
+            maximal_access: FileAccessMask::from(0x001f01ff),
         } => "100001000008000000000000ff011f00"
     }
+
+    #[cfg(feature = "client")]
+    fn make_remoted_identity() -> RemotedIdentityTreeConnect {
+        use binrw::io::Cursor;
+        use smb_dtyp::security::AclRevision;
+        use std::str::FromStr;
+
+        let sid = |s| SID::from_str(s).unwrap();
+        let sid_attr = |s| SidAttrData {
+            sid_data: sid(s),
+            attr: SidAttrSeGroup::new()
+                .with_mandatory(true)
+                .with_enabled_by_default(true)
+                .with_group_enabled(true),
+        };
+        // CLAIM_SECURITY_ATTRIBUTE_RELATIVE_V1, of type INT64 with a single value.
+        let claims = || BlobData {
+            blob_data: ClaimSecurityAttributeRelativeV1::read_le(&mut Cursor::new(
+                smb_tests::hex_to_u8_array! {"00000000010000000000000001000000"},
+            ))
+            .unwrap(),
+        };
+        RemotedIdentityTreeConnect {
+            user: sid_attr("S-1-5-21-782712087-4182988437-2163400469-1002"),
+            user_name: "user".into(),
+            domain: "DOMAIN".into(),
+            groups: ArrayData {
+                list: vec![sid_attr("S-1-5-32-545")],
+            },
+            restricted_groups: ArrayData { list: vec![] },
+            privileges: ArrayData { list: vec![] },
+            primary_group: ArrayData {
+                list: vec![sid_attr("S-1-5-21-782712087-4182988437-2163400469-513")],
+            },
+            owner: BlobData {
+                blob_data: sid("S-1-5-21-782712087-4182988437-2163400469-1002"),
+            },
+            default_dacl: BlobData {
+                blob_data: ACL {
+                    acl_revision: AclRevision::Nt4,
+                    ace: vec![],
+                },
+            },
+            device_groups: ArrayData { list: vec![] },
+            user_claims: claims(),
+            device_claims: claims(),
+        }
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_tree_connect_with_remoted_identity_write() {
+        use binrw::io::Cursor;
+
+        let request =
+            TreeConnectRequest::with_remoted_identity(r"\\server\share", make_remoted_identity());
+        let mut cursor = Cursor::new(Vec::new());
+        PlainRequest::new(request.into())
+            .write(&mut cursor)
+            .unwrap();
+        let bytes = cursor.into_inner();
+        let content = &bytes[Header::STRUCT_SIZE..];
+
+        // StructureSize stays 9, and the extension is present.
+        assert_eq!(&content[0..2], &[9, 0]);
+        let flags = TreeConnectRequestFlags::from_bytes([content[2], content[3]]);
+        assert!(flags.extension_present());
+
+        let context_offset = u32::from_le_bytes(content[8..12].try_into().unwrap()) as usize;
+        let context_count = u16::from_le_bytes(content[12..14].try_into().unwrap());
+        assert_eq!(context_count, 1);
+
+        // The single context is the last item in the message.
+        let context = &bytes[context_offset..];
+        assert_eq!(&context[0..2], &[1, 0]);
+        let data_length = u16::from_le_bytes(context[2..4].try_into().unwrap()) as usize;
+        assert_eq!(data_length, context.len() - 8);
+        // TicketType and TicketSize of the remoted identity.
+        assert_eq!(&context[8..10], &[1, 0]);
+        assert_eq!(
+            u16::from_le_bytes(context[10..12].try_into().unwrap()) as usize,
+            data_length
+        );
+    }
+
+    #[cfg(all(feature = "client", feature = "server"))]
+    #[test]
+    fn test_tree_connect_with_remoted_identity_roundtrip() {
+        use binrw::io::Cursor;
+
+        let request =
+            TreeConnectRequest::with_remoted_identity(r"\\server\share", make_remoted_identity());
+        let mut cursor = Cursor::new(Vec::new());
+        PlainRequest::new(request.into())
+            .write(&mut cursor)
+            .unwrap();
+
+        cursor.set_position(0);
+        let read = PlainRequest::read(&mut cursor).unwrap();
+        assert_eq!(
+            read.content.to_treeconnect().unwrap(),
+            TreeConnectRequest::with_remoted_identity(r"\\server\share", make_remoted_identity())
+        );
+    }
+
+    /// TreeConnectContextOffset counts from the beginning of the SMB2 header,
+    /// not from the offset field, so the body is parsed at a non-zero base offset.
+    #[cfg(all(feature = "client", feature = "server"))]
+    #[test]
+    fn test_tree_connect_context_offset_from_header() {
+        use binrw::io::Cursor;
+
+        let request =
+            TreeConnectRequest::with_remoted_identity(r"\\server\share", make_remoted_identity());
+        let mut cursor = Cursor::new(Vec::new());
+        PlainRequest::new(request.into())
+            .write(&mut cursor)
+            .unwrap();
+
+        let bytes = cursor.into_inner();
+        let content = &bytes[Header::STRUCT_SIZE..];
+        let context_offset = u32::from_le_bytes(content[8..12].try_into().unwrap()) as usize;
+        // Path buffer starts right after the 24-byte extended body; the context follows it.
+        let path_offset = u16::from_le_bytes(content[4..6].try_into().unwrap()) as usize;
+        assert_eq!(path_offset, Header::STRUCT_SIZE + 24);
+        assert!(context_offset > path_offset);
+
+        let mut cursor = Cursor::new(&bytes);
+        cursor.set_position(Header::STRUCT_SIZE as u64);
+        let read = TreeConnectRequest::read_le(&mut cursor).unwrap();
+        assert_eq!(
+            read,
+            TreeConnectRequest::with_remoted_identity(r"\\server\share", make_remoted_identity())
+        );
+    }
+
+    #[test]
+    fn test_remoted_identity_builder_roundtrip() {
+        use binrw::io::Cursor;
+        use std::str::FromStr;
+
+        let user = SID::from_str("S-1-5-21-782712087-4182988437-2163400469-1002").unwrap();
+        let identity = RemotedIdentityTreeConnectBuilder::new(
+            user.clone(),
+            SidAttrSeGroup::new().with_group_enabled(true),
+        )
+        .user_name("user")
+        .domain("DOMAIN")
+        .build();
+        assert_eq!(identity.owner.blob_data, user);
+        assert!(identity.groups.list.is_empty());
+
+        let mut cursor = Cursor::new(Vec::new());
+        identity.write_le(&mut cursor).unwrap();
+        let bytes = cursor.into_inner();
+        // TicketType, and TicketSize covering the whole ticket.
+        assert_eq!(&bytes[0..2], &[1, 0]);
+        assert_eq!(
+            u16::from_le_bytes(bytes[2..4].try_into().unwrap()) as usize,
+            bytes.len()
+        );
+
+        let read = RemotedIdentityTreeConnect::read_le(&mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(read, identity);
+        assert_eq!(read.user.sid_data, user);
+        assert_eq!(read.user_name.to_string(), "user");
+        assert_eq!(read.domain.to_string(), "DOMAIN");
+    }
+
+    #[test]
+    fn test_tree_connect_response_share_helpers() {
+        let response = TreeConnectResponse {
+            share_type: ShareType::Disk,
+            share_flags: ShareFlags::new().with_access_based_directory_enum(true),
+            capabilities: TreeCapabilities::new(),
+            maximal_access: FileAccessMask::from(0x001f01ff),
+        };
+        assert!(response.is_disk());
+        assert!(!response.is_pipe());
+        assert!(!response.is_print());
+        assert!(!response.supports_dfs());
+        assert!(response.maximal_access.file_read_data());
+        assert!(response.maximal_access.file_write_attributes());
+
+        let ipc = TreeConnectResponse {
+            share_type: ShareType::Pipe,
+            capabilities: TreeCapabilities::new().with_dfs(true),
+            ..response
+        };
+        assert!(ipc.is_pipe());
+        assert!(!ipc.is_disk());
+        assert!(ipc.supports_dfs());
+        assert!(!ipc.is_dfs_share());
+        assert!(!ipc.requires_encryption());
+
+        let encrypted = TreeConnectResponse {
+            share_flags: ShareFlags::new().with_dfs(true).with_encrypt_data(true),
+            ..response
+        };
+        assert!(encrypted.is_dfs_share());
+        assert!(encrypted.requires_encryption());
+    }
+
+    #[test]
+    fn test_share_type_and_cache_mode_helpers() {
+        assert!(ShareType::Disk.is_disk());
+        assert!(ShareType::Pipe.is_pipe());
+        assert!(ShareType::Print.is_print());
+        assert!(!ShareType::Pipe.is_disk());
+
+        assert_eq!(ShareCacheMode::Manual.to_string(), "Manual (0x0)");
+        assert_eq!(ShareCacheMode::NoCache.to_string(), "NoCache (0x3)");
+        assert_eq!(ShareCacheMode::All.to_string(), "All (0xf)");
+    }
 }
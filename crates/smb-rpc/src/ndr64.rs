@@ -1,4 +1,14 @@
 //! Data structures for NDR64.
+//!
+//! This is the NDR (Network Data Representation) marshaling layer used for RPC stub data,
+//! in the NDR64 transfer syntax that is negotiated when binding (see [`crate::pdu`]).
+//! Encoding and decoding are done through `binrw`'s [`BinRead`] and [`BinWrite`]:
+//! - [`NdrAlign`] aligns primitives and structures to 8 bytes (or a custom alignment, e.g. 4).
+//! - [`NdrArray`] is a conformant array, with deferred pointees written after the elements.
+//! - [`NdrPtr`] is a unique (nullable) pointer, with a referent ID.
+//! - [`NdrString`] is a conformant and varying string.
+//!
+//! See [`crate::interface::SrvSvc`] for a call (NetrShareEnum) built on top of these types.
 use binrw::prelude::*;
 
 pub mod align;
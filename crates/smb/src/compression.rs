@@ -22,6 +22,29 @@ impl Decompressor {
         &self,
         original: &CompressedMessage,
     ) -> Result<(Response, Vec<u8>), CompressionError> {
+        self.decompress_with_limit(original, usize::MAX)
+    }
+
+    /// Decompresses the message, refusing to produce more than `max` bytes.
+    ///
+    /// The declared original size of the message is checked against `max` before decompressing,
+    /// and the decompression itself fails as soon as it would exceed the declared size.
+    ///
+    /// See [`NegotiatedProperties::max_decompressed_size`][crate::connection::connection_info::NegotiatedProperties::max_decompressed_size]
+    /// for computing a limit from the negotiated connection properties.
+    pub fn decompress_with_limit(
+        &self,
+        original: &CompressedMessage,
+        max: usize,
+    ) -> Result<(Response, Vec<u8>), CompressionError> {
+        let declared_size = original.original_size() as usize;
+        if declared_size > max {
+            return Err(CompressionError::MessageTooLarge {
+                size: declared_size,
+                max,
+            });
+        }
+
         let method: Box<dyn CompressionMethod> = match original {
             CompressedMessage::Unchained(_) => Box::new(UnchainedCompression),
             CompressedMessage::Chained(_) => {
@@ -101,7 +124,12 @@ impl CompressionMethod for UnchainedCompression {
         };
         let mut data: Vec<u8> = Vec::<u8>::with_capacity(compressed.original_size as usize);
         self.get_compression_algorithm(compressed.compression_algorithm)?
-            .decompress(&compressed.data, Some(compressed.original_size), &mut data)?;
+            .decompress(
+                &compressed.data,
+                Some(compressed.original_size),
+                compressed.original_size as usize,
+                &mut data,
+            )?;
         Ok(data)
     }
 
@@ -142,20 +170,18 @@ impl CompressionMethod for ChainedCompression {
             Err(CompressionError::InvalidCompressedMessage)?;
         }
 
-        // TODO: There should be a safer way to implement an append-only,
-        // size-limited vector.
         let mut data = Vec::with_capacity(compressed.original_size as usize);
 
         for item in compressed.items.iter() {
             let len_before = data.len();
             self.get_compression_algorithm(item.compression_algorithm)?
-                .decompress(&item.payload_data, item.original_size, &mut data)?;
+                .decompress(
+                    &item.payload_data,
+                    item.original_size,
+                    compressed.original_size as usize,
+                    &mut data,
+                )?;
             let len_after = data.len();
-            if len_after > compressed.original_size as usize {
-                return Err(CompressionError::ChainedCompressionFailed(
-                    "Decompressed size exceeds the expected size".to_string(),
-                ))?;
-            }
             if let Some(original_size) = item.original_size
                 && len_after - len_before != original_size as usize
             {
@@ -189,10 +215,12 @@ trait CompressionAlgorithmImpl {
     ///
     /// The original size is optional, and is only used for chained decompression.
     ///
+    /// Fails before writing anything if the output buffer would grow beyond `max_size` bytes.
     fn decompress(
         &self,
         compressed: &[u8],
         original_size: Option<u32>,
+        max_size: usize,
         out: &mut Vec<u8>,
     ) -> Result<(), CompressionError>;
 
@@ -200,6 +228,14 @@ trait CompressionAlgorithmImpl {
     fn compress(&self, data: &[u8]) -> Result<Vec<u8>, CompressionError>;
 }
 
+/// Fails if a decompressed output of `size` bytes would exceed `max` bytes.
+fn check_decompressed_size(size: usize, max: usize) -> Result<(), CompressionError> {
+    if size > max {
+        return Err(CompressionError::MessageTooLarge { size, max });
+    }
+    Ok(())
+}
+
 pub const SUPPORTED_ALGORITHMS: &[CompressionAlgorithm] = &[
     CompressionAlgorithm::None,
     #[cfg(feature = "compress_pattern_v1")]
@@ -215,9 +251,11 @@ impl CompressionAlgorithmImpl for NoneCompression {
         &self,
         compressed: &[u8],
         original_size: Option<u32>,
+        max_size: usize,
         out: &mut Vec<u8>,
     ) -> Result<(), CompressionError> {
         debug_assert!(original_size.is_none());
+        check_decompressed_size(out.len() + compressed.len(), max_size)?;
 
         out.extend_from_slice(compressed);
         Ok(())
@@ -252,6 +290,7 @@ impl CompressionAlgorithmImpl for PatternV1Compression {
         &self,
         compressed: &[u8],
         original_size: Option<u32>,
+        max_size: usize,
         out: &mut Vec<u8>,
     ) -> Result<(), CompressionError> {
         debug_assert!(original_size.is_none());
@@ -262,6 +301,7 @@ impl CompressionAlgorithmImpl for PatternV1Compression {
             Ok(p) => p,
             Err(e) => return Err(CompressionError::PatternV1InvalidPayload(e)),
         };
+        check_decompressed_size(out.len() + parsed_payload.repetitions as usize, max_size)?;
         out.extend(std::iter::repeat_n(
             parsed_payload.pattern,
             parsed_payload.repetitions as usize,
@@ -284,9 +324,11 @@ impl CompressionAlgorithmImpl for Lz4Compression {
         &self,
         compressed: &[u8],
         original_size: Option<u32>,
+        max_size: usize,
         out: &mut Vec<u8>,
     ) -> Result<(), CompressionError> {
         let start_index = out.len();
+        check_decompressed_size(start_index + original_size.unwrap() as usize, max_size)?;
         out.resize(start_index + original_size.unwrap() as usize, 0);
 
         let size = lz4_flex::decompress_into(compressed, &mut out[start_index..])?;
@@ -317,6 +359,8 @@ pub enum CompressionError {
     UnsupportedCompressionMethod,
     #[error("There is no supported compression algorithm available.")]
     NoSupportedCompressionAlgorithm,
+    #[error("Decompressed message size ({size}) exceeds the maximum allowed size ({max}).")]
+    MessageTooLarge { size: usize, max: usize },
 
     // --- LZ4
     #[cfg(feature = "compress_lz4")]
@@ -345,7 +389,7 @@ mod tests {
         let compressed = vec![1, 2, 3, 4, 5];
         let mut out = vec![];
         super::NoneCompression
-            .decompress(&compressed, None, &mut out)
+            .decompress(&compressed, None, compressed.len(), &mut out)
             .unwrap();
         assert_eq!(compressed, out);
     }
@@ -356,8 +400,36 @@ mod tests {
         let pattern_v1_payload_buffer = vec!['h' as u8, 0x0, 0x0, 0x0, 0xee, 0x1, 0x0, 0x0];
         let mut out = vec![];
         super::PatternV1Compression
-            .decompress(&pattern_v1_payload_buffer, None, &mut out)
+            .decompress(&pattern_v1_payload_buffer, None, 0x1ee, &mut out)
             .unwrap();
+        assert_eq!(out, vec![b'h'; 0x1ee]);
+    }
+
+    #[cfg(feature = "compress_pattern_v1")]
+    #[test]
+    pub fn test_chained_decompression_exceeds_declared_size() {
+        // The pattern expands way beyond the declared original size.
+        let parsed_message = CompressedMessage::Chained(CompressedChainedMessage {
+            original_size: 0x100,
+            items: vec![CompressedChainedItem {
+                compression_algorithm: CompressionAlgorithm::PatternV1,
+                flags: 0,
+                original_size: None,
+                payload_data: vec![0x64, 0x0, 0x0, 0x0, 0xff, 0xff, 0xff, 0xff],
+            }],
+        });
+
+        let decompressor = Decompressor::new(&CompressionCapabilities {
+            flags: CompressionCapsFlags::new().with_chained(true),
+            compression_algorithms: vec![CompressionAlgorithm::PatternV1],
+        });
+        match decompressor.decompress_with_limit(&parsed_message, 0x1000) {
+            Err(CompressionError::MessageTooLarge { size, max }) => {
+                assert_eq!(size, 0xffffffff);
+                assert_eq!(max, 0x100);
+            }
+            other => panic!("Expected MessageTooLarge error, got {other:?}"),
+        }
     }
 
     #[cfg(feature = "compress_pattern_v1")]
@@ -416,7 +488,7 @@ mod tests {
             plain_unwrapped.header,
             Header {
                 credit_charge: 1,
-                status: Status::Success as u32,
+                status: Status::U32_SUCCESS,
                 command: Command::Read,
                 credit_request: 1,
                 flags: HeaderFlags::new()
@@ -436,8 +508,30 @@ mod tests {
         assert_eq!(
             read_response,
             ReadResponse {
+                flags: ReadResponseFlags::new(),
                 buffer: vec![0x64; 0x400]
             }
         )
     }
+
+    #[test]
+    pub fn test_decompress_with_limit_declared_size_too_large() {
+        let parsed_message = CompressedMessage::Unchained(CompressedUnchainedMessage {
+            original_size: 0x10000,
+            compression_algorithm: CompressionAlgorithm::LZ4,
+            data: vec![0; 16],
+        });
+
+        let decompressor = Decompressor::new(&CompressionCapabilities {
+            flags: CompressionCapsFlags::new(),
+            compression_algorithms: vec![CompressionAlgorithm::LZ4],
+        });
+        match decompressor.decompress_with_limit(&parsed_message, 0x1000) {
+            Err(CompressionError::MessageTooLarge { size, max }) => {
+                assert_eq!(size, 0x10000);
+                assert_eq!(max, 0x1000);
+            }
+            other => panic!("Expected MessageTooLarge error, got {other:?}"),
+        }
+    }
 }
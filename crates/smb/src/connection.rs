@@ -14,12 +14,14 @@ use binrw::prelude::*;
 pub use config::*;
 use connection_info::{ConnectionInfo, NegotiatedProperties};
 use maybe_async::*;
-use rand::RngCore;
-use rand::rngs::OsRng;
 use smb_dtyp::*;
-use smb_msg::{Command, Response, negotiate::*, plain::*, smb1::SMB1NegotiateMessage};
+use smb_msg::{
+    Response,
+    negotiate::*,
+    plain::*,
+    smb1::{MultiProtocolNegotiateResponse, SMB1NegotiateMessage},
+};
 use smb_transport::*;
-use std::cmp::max;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 #[cfg(feature = "multi_threaded")]
@@ -200,12 +202,18 @@ impl Connection {
             log::debug!("Sent SMB1 negotiate request, Receieving SMB2 response");
             // 2. Expect SMB2 negotiate response
             let recieved_bytes = transport.receive().await?;
-            let response = Response::try_from(recieved_bytes.as_ref())?;
+            let response = MultiProtocolNegotiateResponse::try_from(recieved_bytes.as_ref())?;
             let message = match response {
-                Response::Plain(m) => m,
+                MultiProtocolNegotiateResponse::Smb2(Response::Plain(m)) => m,
+                MultiProtocolNegotiateResponse::Smb1(smb1) => {
+                    return Err(Error::InvalidMessage(format!(
+                        "Server only supports SMB1 (selected dialect: {:?})",
+                        smb1.selected_dialect(&SMB1NegotiateMessage::default())
+                    )));
+                }
                 _ => {
                     return Err(Error::InvalidMessage(
-                        "Expected SMB2 negotiate response, got SMB1".to_string(),
+                        "Expected plain SMB2 negotiate response".to_string(),
                     ));
                 }
             };
@@ -370,14 +378,8 @@ impl Connection {
 
         // Context list supported on SMB3.1.1+
         let ctx_list = if supported_dialects.contains(&Dialect::Smb0311) {
-            let mut preauth_integrity_hash = [0u8; 32];
-            OsRng.fill_bytes(&mut preauth_integrity_hash);
             let mut ctx_list = vec![
-                PreauthIntegrityCapabilities {
-                    hash_algorithms: vec![HashAlgorithm::Sha512],
-                    salt: preauth_integrity_hash.to_vec(),
-                }
-                .into(),
+                PreauthIntegrityCapabilities::new_with_random_salt(32).into(),
                 NetnameNegotiateContextId {
                     netname: client_netname.into(),
                 }
@@ -587,14 +589,6 @@ impl ConnectionMessageHandler {
         self.worker.get()
     }
 
-    const SET_CREDIT_CHARGE_CMDS: &'static [Command] = &[
-        Command::Read,
-        Command::Write,
-        Command::Ioctl,
-        Command::QueryDirectory,
-    ];
-
-    const CREDIT_CALC_RATIO: u32 = 65536;
     const CREDITS_PER_MSG_NO_LARGE_MTU: u32 = 1;
 
     #[maybe_async]
@@ -602,16 +596,7 @@ impl ConnectionMessageHandler {
         if let Some(neg) = self.conn_info.get() {
             if neg.negotiation.caps.large_mtu() {
                 // Calculate the cost of the message (charge).
-                let cost = if Self::SET_CREDIT_CHARGE_CMDS.contains(&msg.message.header.command) {
-                    let send_payload_size = msg.message.content.req_payload_size();
-                    let expected_response_payload_size = msg.message.content.expected_resp_size();
-                    (1 + (max(send_payload_size, expected_response_payload_size) - 1)
-                        / Self::CREDIT_CALC_RATIO)
-                        .try_into()
-                        .unwrap()
-                } else {
-                    1
-                };
+                let cost = msg.message.content.credit_charge();
 
                 // First, acquire credits from the semaphore, and forget them.
                 // They may be returned via the response message, at `process_sequence_incoming` below.
@@ -807,7 +792,7 @@ impl MessageHandler for ConnectionMessageHandler {
         if !options
             .status
             .iter()
-            .any(|s| msg.message.header.status == *s as u32)
+            .any(|s| msg.message.header.status == s.as_u32())
         {
             if let ResponseContent::Error(error_res) = msg.message.content {
                 return Err(Error::ReceivedErrorMessage(
@@ -46,6 +46,24 @@ pub struct NegotiatedProperties {
     pub dialect_rev: Dialect,
 }
 
+impl NegotiatedProperties {
+    /// Additional room for the SMB2 header and response structures,
+    /// on top of the negotiated maximum payload sizes.
+    const DECOMPRESSED_MESSAGE_OVERHEAD: usize = 0x1000;
+
+    /// Returns the maximum size of a decompressed message that may be received on this connection,
+    /// derived from the negotiated maximum read, write and transaction sizes.
+    ///
+    /// This is used as a safety cap when decompressing incoming messages.
+    pub fn max_decompressed_size(&self) -> usize {
+        let max_payload = self
+            .max_read_size
+            .max(self.max_transact_size)
+            .max(self.max_write_size) as usize;
+        max_payload + Self::DECOMPRESSED_MESSAGE_OVERHEAD
+    }
+}
+
 /// This struct is initalized once a connection is established and negotiated.
 /// It contains all the information about the connection.
 #[derive(Debug)]
@@ -28,14 +28,7 @@ impl PreauthHashState {
     pub fn next(self, data: &IoVec) -> PreauthHashState {
         match self {
             PreauthHashState::InProgress(hash) => {
-                let mut hasher = Sha512::new();
-                hasher.update(hash);
-
-                for data in data.iter() {
-                    hasher.update(data.as_ref());
-                }
-
-                PreauthHashState::InProgress(hasher.finalize().into())
+                PreauthHashState::InProgress(hash_step(&hash, data.iter().map(|d| d.as_ref())))
             }
             PreauthHashState::Unsupported => PreauthHashState::Unsupported,
             _ => panic!("Preauth hash not started/already finished."),
@@ -58,3 +51,90 @@ impl PreauthHashState {
         }
     }
 }
+
+/// Computes the final SMB 3.1.1 preauth integrity hash over a sequence of messages.
+///
+/// `messages` should contain the serialized messages in the order they were sent or received:
+/// the negotiate request and response, followed by the session setup requests and
+/// the interim session setup responses.
+pub fn compute_preauth_hash(messages: &[&[u8]]) -> PreauthHashValue {
+    messages
+        .iter()
+        .fold([0; 64], |hash, message| hash_step(&hash, [*message]))
+}
+
+fn hash_step<'a>(
+    hash: &PreauthHashValue,
+    data: impl IntoIterator<Item = &'a [u8]>,
+) -> PreauthHashValue {
+    let mut hasher = Sha512::new();
+    hasher.update(hash);
+    for data in data {
+        hasher.update(data);
+    }
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static NEGOTIATE_REQUEST: &[u8] = &[
+        0x24, 0x00, 0x05, 0x00, 0x01, 0x00, 0x00, 0x00, 0xff, 0x00, 0x00, 0x00, 0xdf, 0x0d, 0x2e,
+        0xc1, 0xdd, 0x43, 0xf0, 0x11, 0x8b, 0x87, 0x00, 0x0c, 0x29, 0x80, 0x16, 0x82, 0x70, 0x00,
+        0x00, 0x00, 0x06, 0x00, 0x00, 0x00, 0x02, 0x02, 0x10, 0x02, 0x00, 0x03, 0x02, 0x03, 0x11,
+        0x03, 0x00, 0x00, 0x01, 0x00, 0x26, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x20, 0x00,
+        0x01, 0x00, 0xed, 0x00, 0x6c, 0x30, 0x4e, 0x33, 0x28, 0x90, 0xb2, 0xbd, 0x98, 0x61, 0x7b,
+        0x5a, 0xd9, 0xef, 0x07, 0x59, 0x94, 0x15, 0x46, 0x73, 0x69, 0x62, 0x80, 0xff, 0xcc, 0x0f,
+        0x12, 0x91, 0xa1, 0x5d, 0x00, 0x00, 0x02, 0x00, 0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04,
+        0x00, 0x02, 0x00, 0x01, 0x00, 0x04, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x03, 0x00, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+        0x00, 0x04, 0x00, 0x02, 0x00, 0x03, 0x00, 0x01, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x08, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x02, 0x00, 0x01,
+        0x00, 0x00, 0x00, 0x05, 0x00, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00, 0x6c, 0x00, 0x6f, 0x00,
+        0x63, 0x00, 0x61, 0x00, 0x6c, 0x00, 0x68, 0x00, 0x6f, 0x00, 0x73, 0x00, 0x74, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00,
+    ];
+
+    static NEGOTIATE_RESPONSE: &[u8] = &[
+        0x41, 0x00, 0x01, 0x00, 0x11, 0x03, 0x05, 0x00, 0xb9, 0x21, 0xf8, 0xe0, 0x15, 0x07, 0xaa,
+        0x41, 0xbe, 0x38, 0x67, 0xfe, 0xbf, 0x5e, 0x2e, 0x11, 0x2f, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x80, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x80, 0x00, 0xa8, 0x76, 0xd8, 0x78, 0xc5,
+        0x69, 0xdb, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x2a, 0x00,
+        0xb0, 0x00, 0x00, 0x00, 0x60, 0x28, 0x06, 0x06, 0x2b, 0x06, 0x01, 0x05, 0x05, 0x02, 0xa0,
+        0x1e, 0x30, 0x1c, 0xa0, 0x1a, 0x30, 0x18, 0x06, 0x0a, 0x2b, 0x06, 0x01, 0x04, 0x01, 0x82,
+        0x37, 0x02, 0x02, 0x1e, 0x06, 0x0a, 0x2b, 0x06, 0x01, 0x04, 0x01, 0x82, 0x37, 0x02, 0x02,
+        0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x26, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x20, 0x00, 0x01, 0x00, 0xd5, 0x67, 0x1b, 0x24, 0xa1, 0xe9, 0xcc, 0xc8, 0x93,
+        0xf5, 0x55, 0x5a, 0x31, 0x03, 0x43, 0x5a, 0x85, 0x2b, 0xc3, 0xcb, 0x1a, 0xd3, 0x2d, 0xc5,
+        0x1f, 0x92, 0x80, 0x6e, 0xf3, 0xfb, 0x4d, 0xd4, 0x00, 0x00, 0x02, 0x00, 0x04, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00, 0x04, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x00, 0x0c,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x04, 0x00,
+    ];
+
+    static EXPECTED_HASH: PreauthHashValue = [
+        0x8a, 0xdb, 0xa2, 0x53, 0x7b, 0xaf, 0x3c, 0xd0, 0x29, 0x1f, 0xa2, 0x63, 0xd7, 0x61, 0xae,
+        0x85, 0x62, 0x2e, 0x00, 0xf1, 0x5e, 0x40, 0x40, 0xcb, 0xcd, 0x96, 0xbf, 0x52, 0x39, 0x9b,
+        0x13, 0xa4, 0xb9, 0x82, 0x52, 0x8a, 0x10, 0xc7, 0x20, 0x51, 0xdc, 0x48, 0x5a, 0x70, 0x99,
+        0xf2, 0x56, 0x75, 0xb8, 0xac, 0x5a, 0x14, 0xe7, 0x4c, 0xa9, 0x9b, 0x03, 0xa9, 0x55, 0x65,
+        0xc0, 0xf3, 0x68, 0x53,
+    ];
+
+    #[test]
+    fn test_compute_preauth_hash() {
+        let hash = compute_preauth_hash(&[NEGOTIATE_REQUEST, NEGOTIATE_RESPONSE]);
+        assert_eq!(hash, EXPECTED_HASH);
+
+        let state = PreauthHashState::begin()
+            .next(&NEGOTIATE_REQUEST.to_vec().into())
+            .next(&NEGOTIATE_RESPONSE.to_vec().into())
+            .finish();
+        assert_eq!(state.unwrap_final_hash(), Some(&hash));
+
+        assert_eq!(compute_preauth_hash(&[]), [0; 64]);
+    }
+}
@@ -27,6 +27,8 @@ pub struct Transformer {
 struct TransformerConfig {
     /// Compressors for this connection.
     compress: Option<(Compressor, Decompressor)>,
+    /// Maximum size of a decompressed incoming message.
+    max_decompressed_size: usize,
 
     negotiated: bool,
 }
@@ -53,6 +55,7 @@ impl Transformer {
                 .as_ref()
                 .map(|c| (Compressor::new(c), Decompressor::new(c)));
             config.compress = compress;
+            config.max_decompressed_size = neg_info.negotiation.max_decompressed_size();
         }
 
         config.negotiated = true;
@@ -296,7 +299,9 @@ impl Transformer {
             let rconfig = self.config.read().await?;
             form.compressed = true;
             match &rconfig.compress {
-                Some(compress) => compress.1.decompress(&compressed_message)?,
+                Some(compress) => compress
+                    .1
+                    .decompress_with_limit(&compressed_message, rconfig.max_decompressed_size)?,
                 None => {
                     return Err(crate::Error::TranformFailed(TransformError {
                         outgoing: false,
@@ -354,7 +359,7 @@ impl Transformer {
         // Check if signing check is required.
         if form.encrypted
             || message.header.message_id == u64::MAX
-            || message.header.status == Status::Pending as u32
+            || message.header.status == Status::U32_PENDING
             || !(message.header.flags.signed() || self.is_message_signed_ksmbd(message).await)
         {
             return Ok(());
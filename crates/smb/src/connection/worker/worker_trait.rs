@@ -105,7 +105,7 @@ pub trait Worker: Sized + std::fmt::Debug {
         }
 
         // If not pending, that's the result, right away!
-        if curr.message.header.status != Status::Pending as u32 {
+        if curr.message.header.status != Status::U32_PENDING {
             return Ok(curr);
         }
 
@@ -148,7 +148,7 @@ pub trait Worker: Sized + std::fmt::Debug {
             }
 
             // We've got a result!
-            if msg.message.header.status != Status::Pending as u32 {
+            if msg.message.header.status != Status::U32_PENDING {
                 return Ok(msg);
             }
 
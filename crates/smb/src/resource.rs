@@ -154,7 +154,8 @@ impl Resource {
         let is_dir = response.file_attributes.directory();
 
         // Get maximal access
-        let access = CreateContextResponseData::first_mxac(&response.create_contexts)
+        let access = response
+            .maximal_access()
             .and_then(|r| r.maximal_access())
             .unwrap_or_else(|| {
                     log::debug!(
@@ -362,7 +363,7 @@ impl ResourceHandle {
 
         match result {
             Ok(response) => {
-                let status = response.message.header.status.try_into().unwrap();
+                let status = response.message.header.status();
                 match status {
                     Status::Success => {
                         Ok(response.message.content.to_queryinfo()?.parse(info_type)?)
@@ -413,7 +414,7 @@ impl ResourceHandle {
     where
         T: Into<SetInfoData>,
     {
-        let data = data.into().to_req(cls, self.file_id()?, additional_info);
+        let data = data.into().to_req(cls, self.file_id()?, additional_info)?;
         let response = self.send_receive(data.into()).await?;
         response.message.content.to_setinfo()?;
         Ok(())
@@ -247,6 +247,14 @@ impl MessageHandler for ChannelMessageHandler {
                 match s2c_notification.notification {
                     // TODO: Move this to primary session
                     Notification::NotifySessionClosed(_) => self._invalidate().await,
+                    Notification::Unknown {
+                        notification_type, ..
+                    } => {
+                        log::warn!(
+                            "Received unknown server to client notification type: {notification_type:#x}"
+                        );
+                        Ok(())
+                    }
                 }
             }
             _ => {
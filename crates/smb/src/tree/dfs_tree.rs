@@ -2,7 +2,7 @@ use std::ops::Deref;
 
 use crate::msg_handler::{MessageHandler, ReceiveOptions};
 use maybe_async::*;
-use smb_msg::{FileId, FsctlCodes, IoctlReqData, IoctlRequest, IoctlRequestFlags, dfsc::*};
+use smb_msg::{IoctlRequest, dfsc::*};
 
 use super::Tree;
 
@@ -29,18 +29,7 @@ impl<'a> DfsRootTreeRef<'a> {
         let res = self
             .handler
             .send_recvo(
-                IoctlRequest {
-                    ctl_code: FsctlCodes::DfsGetReferrals as u32,
-                    file_id: FileId::FULL,
-                    max_input_response: 1024,
-                    max_output_response: 1024,
-                    flags: IoctlRequestFlags::new().with_is_fsctl(true),
-                    buffer: IoctlReqData::FsctlDfsGetReferrals(ReqGetDfsReferral {
-                        max_referral_level: ReferralLevel::V4,
-                        request_file_name: path.into(),
-                    }),
-                }
-                .into(),
+                IoctlRequest::dfs_referral(path, ReferralLevel::V4 as u16)?.into(),
                 ReceiveOptions::new().with_allow_async(true),
             )
             .await?;
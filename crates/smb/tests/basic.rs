@@ -88,7 +88,7 @@ async fn do_test_basic_auth_fail() -> smb::Result<()> {
     let res = _do_minimal_connection_test(None, None).await.unwrap_err();
     match res {
         smb::Error::UnexpectedMessageStatus(status) => {
-            assert_eq!(status, Status::LogonFailure as u32);
+            assert_eq!(status, Status::U32_LOGON_FAILURE);
         }
         _ => panic!("Expected LogonFailure error"),
     }